@@ -1,12 +1,41 @@
 pub mod sql_query;
+pub mod sql_query_rule;
 
 pub mod sql_query_param;
 pub mod sql_query_param_type;
+pub mod sql_param_constraints;
+pub mod sql_default_value;
 
 pub mod sql_dynamic_query;
 pub mod sql_dynamic_query_data;
+pub mod sql_param_validation_report;
+pub mod sql_json_row;
+pub mod sql_column_policy;
+pub mod sql_safety;
+pub mod sql_execution_context;
+pub mod sql_backend;
 
 pub mod sql_query_manager;
+pub mod sql_query_manager_builder;
+pub mod sql_query_cache;
+pub mod sql_replica_set;
+pub mod sql_query_definition;
+pub mod sql_query_repository;
+pub mod sql_pg_query_repository;
+pub mod sql_sqlx_query_repository;
+pub mod sql_memory_query_repository;
+pub mod sql_file_query_repository;
+pub mod sql_front_matter;
+pub mod sql_registry_document;
+pub mod sql_import;
+pub mod sql_registry_diff;
+pub mod sql_query_revision;
+pub mod sql_query_status;
+pub mod sql_query_result;
+pub mod sql_layered_query_repository;
+pub mod sql_audit;
+pub mod sql_audit_pg_sink;
+pub mod sql_query_limits;
 pub mod sql_query_engine_error;
 
 pub mod test;
\ No newline at end of file
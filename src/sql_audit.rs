@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::sql_dynamic_query_data::SqlDynamicQueryData;
+use crate::sql_execution_context::ExecutionContext;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+
+/// Trace d'une exécution de requête dynamique : qui a exécuté quelle requête,
+/// avec quels paramètres, quand, pendant combien de temps et avec quel résultat.
+/// # Champs
+/// - `item_key`: Clé de la requête exécutée
+/// - `params`: Paramètres fournis par l'appelant (valeurs masquées pour les paramètres sensibles
+///   et ceux déclarés avec `SqlAuditor::with_redacted_param`)
+/// - `user_id`, `tenant_id`, `roles`, `context_values`: Contexte d'exécution de l'appelant
+/// - `executed_at`: Début de l'exécution
+/// - `duration`: Durée de l'exécution
+/// - `row_count`: Nombre de lignes renvoyées (`None` en cas d'erreur)
+/// - `error_code`: Code de l'erreur (`SqlQueryEngineError::code`), `None` en cas de succès
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlAuditRecord
+{
+    pub item_key: String,
+    pub params: BTreeMap<String, String>,
+    pub user_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub roles: Vec<String>,
+    pub context_values: BTreeMap<String, String>,
+    pub executed_at: DateTime<Utc>,
+    pub duration: Duration,
+    pub row_count: Option<usize>,
+    pub error_code: Option<String>,
+}
+
+/// Destination des traces d'audit (table PostgreSQL, journal, file de messages...).
+///
+/// Une erreur renvoyée par `record` fait échouer l'exécution d'une requête réussie :
+/// aucun résultat n'est renvoyé sans avoir été tracé.
+#[async_trait]
+pub trait SqlAuditSink: fmt::Debug + Send + Sync
+{
+    /// Enregistre une trace d'exécution.
+    async fn record(&self, record: &SqlAuditRecord) -> Result<(), SqlQueryEngineError>;
+}
+
+/// Audit des exécutions : construit les traces et les transmet à une `SqlAuditSink`.
+/// Les valeurs des paramètres sensibles (`SqlQueryParam::sensitive`) ou déclarés comme masqués
+/// sont remplacées par `***`.
+/// Les clones partagent la même destination.
+#[derive(Debug, Clone)]
+pub struct SqlAuditor
+{
+    sink: Arc<dyn SqlAuditSink>,
+    redacted_params: HashSet<String>,
+}
+
+impl SqlAuditor {
+    /// Valeur remplaçant celle d'un paramètre masqué
+    pub const REDACTED: &'static str = SqlQueryParam::REDACTED;
+
+    /// Crée un audit vers la destination donnée.
+    pub fn new(sink: impl SqlAuditSink + 'static) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            redacted_params: HashSet::new(),
+        }
+    }
+
+    /// Masque la valeur d'un paramètre dans les traces (ex : `mot_de_passe`, `numero_secu`).
+    pub fn with_redacted_param(mut self, param_name: impl Into<String>) -> Self {
+        self.redacted_params.insert(param_name.into());
+        self
+    }
+
+    /// Indique si la valeur du paramètre est masquée dans les traces.
+    pub fn is_redacted(&self, param_name: &str) -> bool {
+        self.redacted_params.contains(param_name)
+    }
+
+    /// Construit la trace d'une exécution.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres fournis par l'appelant
+    /// * `context`: Contexte d'exécution de l'appelant
+    /// * `executed_at`: Début de l'exécution
+    /// * `duration`: Durée de l'exécution
+    /// * `outcome`: Nombre de lignes renvoyées ou erreur
+    pub fn build_record(
+        &self,
+        dynamic_query_data: &SqlDynamicQueryData,
+        context: &ExecutionContext,
+        executed_at: DateTime<Utc>,
+        duration: Duration,
+        outcome: Result<usize, &SqlQueryEngineError>,
+    ) -> SqlAuditRecord
+    {
+        let mut params = dynamic_query_data.redacted_params();
+        for (name, value) in params.iter_mut() {
+            if self.is_redacted(name) {
+                *value = Self::REDACTED.to_string();
+            }
+        }
+
+        SqlAuditRecord {
+            item_key: dynamic_query_data.item_key.clone(),
+            params,
+            user_id: context.user_id.clone(),
+            tenant_id: context.tenant_id.clone(),
+            roles: context.roles.clone(),
+            context_values: context.values.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            executed_at,
+            duration,
+            row_count: outcome.as_ref().ok().copied(),
+            error_code: outcome.err().map(|e| e.code().to_string()),
+        }
+    }
+
+    /// Exécute `execution` et trace son résultat.
+    /// Si l'exécution réussit mais que la trace ne peut pas être enregistrée, l'erreur de la destination est renvoyée ;
+    /// si l'exécution échoue, son erreur est renvoyée dans tous les cas.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres fournis par l'appelant
+    /// * `context`: Contexte d'exécution de l'appelant
+    /// * `execution`: L'exécution à tracer
+    pub async fn audit<T, F>(
+        &self,
+        dynamic_query_data: &SqlDynamicQueryData,
+        context: &ExecutionContext,
+        execution: F,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        F: std::future::Future<Output = Result<Vec<T>, SqlQueryEngineError>>,
+    {
+        let executed_at = Utc::now();
+        let started = Instant::now();
+        let result = execution.await;
+
+        let outcome = result.as_ref().map(Vec::len);
+        let record = self.build_record(dynamic_query_data, context, executed_at, started.elapsed(), outcome);
+        let recorded = self.sink.record(&record).await;
+
+        match (result, recorded) {
+            (Ok(_), Err(e)) => Err(e),
+            (result, _) => result,
+        }
+    }
+}
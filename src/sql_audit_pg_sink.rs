@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use crate::sql_audit::{SqlAuditRecord, SqlAuditSink};
+use crate::sql_query_engine_error::SqlQueryEngineError;
+
+/// Destination d'audit écrivant chaque trace dans une table PostgreSQL.
+/// La table doit contenir les colonnes suivantes (voir `create_table`) :
+/// - `item_key`: Clé de la requête exécutée (`TEXT`)
+/// - `params`: Paramètres fournis, valeurs sensibles masquées (`JSONB`)
+/// - `user_id`, `tenant_id`: Identifiants de l'appelant (`TEXT`, nullable)
+/// - `roles`: Rôles de l'appelant (`TEXT[]`)
+/// - `context_values`: Valeurs supplémentaires du contexte (`JSONB`)
+/// - `executed_at`: Début de l'exécution (`TIMESTAMPTZ`)
+/// - `duration_ms`: Durée de l'exécution en millisecondes (`BIGINT`)
+/// - `row_count`: Nombre de lignes renvoyées (`BIGINT`, nullable)
+/// - `error_code`: Code de l'erreur (`TEXT`, nullable)
+#[derive(Debug, Clone)]
+pub struct SqlAuditPgSink
+{
+    pool: PgPool,
+    table: String,
+}
+
+impl SqlAuditPgSink {
+    /// Nom par défaut de la table d'audit
+    pub const DEFAULT_TABLE: &'static str = "sql_query_audit";
+
+    /// Crée une destination écrivant dans la table `sql_query_audit`.
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base de données
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_table(pool, Self::DEFAULT_TABLE)
+    }
+
+    /// Crée une destination écrivant dans la table donnée (éventuellement qualifiée par un schéma).
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base de données
+    /// * `table`: Nom de la table d'audit
+    pub fn with_table(pool: PgPool, table: impl Into<String>) -> Self {
+        Self {
+            pool,
+            table: table.into(),
+        }
+    }
+
+    /// Retourne le nom de la table d'audit.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Crée la table d'audit si elle n'existe pas.
+    pub async fn create_table(&self) -> Result<(), SqlQueryEngineError>
+    {
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id BIGSERIAL PRIMARY KEY,
+                item_key TEXT NOT NULL,
+                params JSONB NOT NULL,
+                user_id TEXT,
+                tenant_id TEXT,
+                roles TEXT[] NOT NULL,
+                context_values JSONB NOT NULL,
+                executed_at TIMESTAMPTZ NOT NULL,
+                duration_ms BIGINT NOT NULL,
+                row_count BIGINT,
+                error_code TEXT
+            )",
+            self.table
+        );
+
+        sqlx::query(query.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|source| self.audit_error(String::new(), source))?;
+        Ok(())
+    }
+
+    fn audit_error(&self, item_key: String, source: sqlx::Error) -> SqlQueryEngineError {
+        SqlQueryEngineError::ErrorAudit {
+            table: self.table.clone(),
+            item_key,
+            source,
+        }
+    }
+}
+
+#[async_trait]
+impl SqlAuditSink for SqlAuditPgSink
+{
+    async fn record(&self, record: &SqlAuditRecord) -> Result<(), SqlQueryEngineError>
+    {
+        let query = format!(
+            "INSERT INTO {} (item_key, params, user_id, tenant_id, roles, context_values, executed_at, duration_ms, row_count, error_code)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            self.table
+        );
+
+        sqlx::query(query.as_str())
+            .bind(&record.item_key)
+            .bind(Json(&record.params))
+            .bind(&record.user_id)
+            .bind(&record.tenant_id)
+            .bind(&record.roles)
+            .bind(Json(&record.context_values))
+            .bind(record.executed_at)
+            .bind(i64::try_from(record.duration.as_millis()).unwrap_or(i64::MAX))
+            .bind(record.row_count.map(|count| i64::try_from(count).unwrap_or(i64::MAX)))
+            .bind(&record.error_code)
+            .execute(&self.pool)
+            .await
+            .map_err(|source| self.audit_error(record.item_key.clone(), source))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,399 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use sqlx::pool::PoolConnection;
+use sqlx::query::QueryAs;
+use sqlx::{Database, FromRow, IntoArguments, MySql, Pool, Postgres, Sqlite};
+use crate::sql_dynamic_query::SqlDynamicQuery;
+use crate::sql_execution_context::ExecutionContext;
+use crate::sql_pg_query_repository::PgQueryRepository;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_safety::SqlDialect;
+use crate::sql_sqlx_query_repository::{MySqlQueryRepository, SqliteQueryRepository};
+
+/// Valeur d'un paramètre convertie selon son type déclaré (`SqlQueryParamType`), prête à être liée à la requête.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlBindValue
+{
+    String(String),
+    I32(i32),
+    F64(f64),
+    Bool(bool),
+    NaiveDate(chrono::NaiveDate),
+    NaiveDateTime(chrono::NaiveDateTime),
+}
+
+/// Base de données sur laquelle le moteur sait lire le registre et exécuter les requêtes
+/// (`sqlx::Postgres`, `sqlx::Sqlite`, `sqlx::MySql`).
+///
+/// Le code SQL stocké utilise les placeholders `$1, $2, ...` quel que soit le backend :
+/// `prepare_sql` les traduit si la base en attend d'autres.
+#[async_trait]
+pub trait SqlBackend: Database
+{
+    /// Traduit les placeholders du code SQL stocké dans la syntaxe du backend,
+    /// pour un code dont les valeurs sont liées dans l'ordre de leurs numéros.
+    fn translate_placeholders(sql: &str) -> Cow<'_, str> {
+        Cow::Borrowed(sql)
+    }
+
+    /// Traduit le code SQL stocké dans la syntaxe du backend (placeholders)
+    /// et renvoie les valeurs dans l'ordre où elles doivent être liées.
+    ///
+    /// # Returns
+    /// * `Err(usize)` - Le numéro d'un placeholder `$n` sans valeur correspondante
+    fn prepare_sql(sql: &str, values: Vec<SqlBindValue>) -> Result<(Cow<'_, str>, Vec<SqlBindValue>), usize> {
+        Ok((Cow::Borrowed(sql), values))
+    }
+
+    /// Dialecte dans lequel le code SQL des requêtes est analysé (voir `SqlQuery::check_safety`).
+    fn sql_dialect() -> SqlDialect;
+
+    /// Indique si l'erreur provient d'une écriture refusée en lecture seule.
+    fn is_read_only_violation(error: &sqlx::Error) -> bool;
+
+    /// Dépôt lisant les tables des requêtes et des paramètres sur ce backend
+    /// (dépôt par défaut de `SqlQueryManager`).
+    fn query_repository(pool: Pool<Self>, table_query: String, table_query_params: String) -> Arc<dyn QueryRepository>;
+
+    /// Exécute la requête avec les valeurs données, en lecture seule si demandé,
+    /// en refusant plus de `max_rows` lignes si une limite est donnée.
+    /// Utilisée par `SqlDynamicQuery::execute_on`, qui vérifie les paramètres et applique le délai maximal.
+    async fn fetch_dynamic<T>(
+        dynamic_query: &SqlDynamicQuery,
+        pool: &Pool<Self>,
+        values: Vec<SqlBindValue>,
+        context: &ExecutionContext,
+        read_only: bool,
+        max_rows: Option<usize>,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> FromRow<'r, Self::Row> + Send + Unpin;
+}
+
+/// Lie les valeurs à la requête dans l'ordre des paramètres.
+macro_rules! bind_values {
+    ($query:expr, $values:expr) => {{
+        let mut query = $query;
+        for value in $values {
+            query = match value {
+                SqlBindValue::String(v) => query.bind(v),
+                SqlBindValue::I32(v) => query.bind(v),
+                SqlBindValue::F64(v) => query.bind(v),
+                SqlBindValue::Bool(v) => query.bind(v),
+                SqlBindValue::NaiveDate(v) => query.bind(v),
+                SqlBindValue::NaiveDateTime(v) => query.bind(v),
+            };
+        }
+        query
+    }};
+}
+
+#[async_trait]
+impl SqlBackend for Postgres
+{
+    fn sql_dialect() -> SqlDialect {
+        SqlDialect::PostgreSql
+    }
+
+    fn is_read_only_violation(error: &sqlx::Error) -> bool {
+        // 25006 : read_only_sql_transaction
+        matches!(error, sqlx::Error::Database(e) if e.code().as_deref() == Some("25006"))
+    }
+
+    fn query_repository(pool: Pool<Self>, table_query: String, table_query_params: String) -> Arc<dyn QueryRepository> {
+        Arc::new(PgQueryRepository::new(pool, table_query, table_query_params))
+    }
+
+    /// Si le contexte n'est pas vide, la requête est exécutée dans une transaction après application
+    /// des variables de session (voir `SqlDynamicQuery::apply_session_settings`) ; en lecture seule,
+    /// la transaction est ouverte avec `START TRANSACTION READ ONLY`.
+    async fn fetch_dynamic<T>(
+        dynamic_query: &SqlDynamicQuery,
+        pool: &Pool<Self>,
+        values: Vec<SqlBindValue>,
+        context: &ExecutionContext,
+        read_only: bool,
+        max_rows: Option<usize>,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> FromRow<'r, Self::Row> + Send + Unpin,
+    {
+        let (sql, values) = prepare_dynamic_sql::<Self>(dynamic_query, values)?;
+        let query = bind_values!(sqlx::query_as::<Self, T>(&sql), values);
+        let error = |source| dynamic_query.execution_error::<Self>(source);
+
+        if context.is_empty() && !read_only {
+            return fetch_rows(dynamic_query, query, pool, max_rows).await;
+        }
+
+        let settings = context.session_settings().map_err(|reason| SqlQueryEngineError::ErrorInvalidContext {
+            item_key: dynamic_query.query.item_key.clone(),
+            reason,
+        })?;
+        let transaction = match read_only {
+            true => pool.begin_with("START TRANSACTION READ ONLY").await,
+            false => pool.begin().await,
+        };
+        let mut transaction = transaction.map_err(error)?;
+        SqlDynamicQuery::apply_session_settings(&mut transaction, &settings).await.map_err(error)?;
+
+        let rows = fetch_rows(dynamic_query, query, &mut *transaction, max_rows).await?;
+
+        transaction.commit().await.map_err(error)?;
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl SqlBackend for Sqlite
+{
+    fn sql_dialect() -> SqlDialect {
+        SqlDialect::Sqlite
+    }
+
+    fn is_read_only_violation(error: &sqlx::Error) -> bool {
+        // 8 : SQLITE_READONLY (écriture refusée par `PRAGMA query_only`)
+        matches!(error, sqlx::Error::Database(e) if e.code().as_deref() == Some("8"))
+    }
+
+    fn query_repository(pool: Pool<Self>, table_query: String, table_query_params: String) -> Arc<dyn QueryRepository> {
+        Arc::new(SqliteQueryRepository::new(pool, table_query, table_query_params))
+    }
+
+    /// SQLite n'a pas de variables de session : le contexte ne sert qu'aux valeurs par défaut.
+    /// En lecture seule, la connexion est passée en `PRAGMA query_only` le temps de la requête
+    /// (et fermée si elle ne peut pas être rétablie, voir `QueryOnlyConnection`).
+    async fn fetch_dynamic<T>(
+        dynamic_query: &SqlDynamicQuery,
+        pool: &Pool<Self>,
+        values: Vec<SqlBindValue>,
+        _context: &ExecutionContext,
+        read_only: bool,
+        max_rows: Option<usize>,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> FromRow<'r, Self::Row> + Send + Unpin,
+    {
+        let (sql, values) = prepare_dynamic_sql::<Self>(dynamic_query, values)?;
+        let query = bind_values!(sqlx::query_as::<Self, T>(&sql), values);
+        let error = |source| dynamic_query.execution_error::<Self>(source);
+
+        if !read_only {
+            return fetch_rows(dynamic_query, query, pool, max_rows).await;
+        }
+
+        let mut connection = QueryOnlyConnection {
+            connection: pool.acquire().await.map_err(error)?,
+            restored: false,
+        };
+        sqlx::query("PRAGMA query_only = ON").execute(&mut *connection.connection).await.map_err(error)?;
+
+        let rows = fetch_rows(dynamic_query, query, &mut *connection.connection, max_rows).await;
+
+        connection.restored = sqlx::query("PRAGMA query_only = OFF").execute(&mut *connection.connection).await.is_ok();
+        rows
+    }
+}
+
+/// Connexion SQLite passée en `PRAGMA query_only = ON`. Elle est fermée au lieu d'être rendue au pool
+/// tant que le mode n'a pas été rétabli, y compris si l'exécution est abandonnée
+/// (délai maximal dépassé, tâche de l'appelant annulée) : les écritures suivantes ne tombent pas
+/// sur une connexion restée en lecture seule.
+struct QueryOnlyConnection
+{
+    connection: PoolConnection<Sqlite>,
+    restored: bool,
+}
+
+impl Drop for QueryOnlyConnection {
+    fn drop(&mut self) {
+        if !self.restored {
+            self.connection.close_on_drop();
+        }
+    }
+}
+
+#[async_trait]
+impl SqlBackend for MySql
+{
+    fn sql_dialect() -> SqlDialect {
+        SqlDialect::MySql
+    }
+
+    fn translate_placeholders(sql: &str) -> Cow<'_, str> {
+        Cow::Owned(question_mark_placeholders(sql).0)
+    }
+
+    /// MySQL n'accepte que des placeholders `?` : chaque `$n` est remplacé par `?` et la valeur `n`
+    /// est liée à sa position (une valeur utilisée deux fois est liée deux fois).
+    fn prepare_sql(sql: &str, values: Vec<SqlBindValue>) -> Result<(Cow<'_, str>, Vec<SqlBindValue>), usize> {
+        let (sql, order) = question_mark_placeholders(sql);
+        let values = order.iter()
+            .map(|index| values.get(index - 1).cloned().ok_or(*index))
+            .collect::<Result<_, _>>()?;
+        Ok((Cow::Owned(sql), values))
+    }
+
+    fn is_read_only_violation(error: &sqlx::Error) -> bool {
+        // 25006 : ER_CANT_EXECUTE_IN_READ_ONLY_TRANSACTION
+        matches!(error, sqlx::Error::Database(e) if e.code().as_deref() == Some("25006"))
+    }
+
+    fn query_repository(pool: Pool<Self>, table_query: String, table_query_params: String) -> Arc<dyn QueryRepository> {
+        Arc::new(MySqlQueryRepository::new(pool, table_query, table_query_params))
+    }
+
+    /// Les variables de session PostgreSQL n'ont pas d'équivalent : le contexte ne sert qu'aux valeurs par défaut.
+    /// En lecture seule, la requête est exécutée dans une transaction `START TRANSACTION READ ONLY`.
+    async fn fetch_dynamic<T>(
+        dynamic_query: &SqlDynamicQuery,
+        pool: &Pool<Self>,
+        values: Vec<SqlBindValue>,
+        _context: &ExecutionContext,
+        read_only: bool,
+        max_rows: Option<usize>,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> FromRow<'r, Self::Row> + Send + Unpin,
+    {
+        let (sql, values) = prepare_dynamic_sql::<Self>(dynamic_query, values)?;
+        let query = bind_values!(sqlx::query_as::<Self, T>(&sql), values);
+        let error = |source| dynamic_query.execution_error::<Self>(source);
+
+        if !read_only {
+            return fetch_rows(dynamic_query, query, pool, max_rows).await;
+        }
+
+        let mut transaction = pool.begin_with("START TRANSACTION READ ONLY").await.map_err(error)?;
+        let rows = fetch_rows(dynamic_query, query, &mut *transaction, max_rows).await?;
+
+        transaction.commit().await.map_err(error)?;
+        Ok(rows)
+    }
+}
+
+/// Prépare le code SQL d'une requête dynamique pour le backend (voir `SqlBackend::prepare_sql`).
+///
+/// # Returns
+/// * `Err(SqlQueryEngineError::ErrorMissingParam)` - Si un placeholder `$n` n'a pas de valeur (`param_name` : `$n`)
+fn prepare_dynamic_sql<DB: SqlBackend>(
+    dynamic_query: &SqlDynamicQuery,
+    values: Vec<SqlBindValue>,
+) -> Result<(Cow<'_, str>, Vec<SqlBindValue>), SqlQueryEngineError>
+{
+    DB::prepare_sql(dynamic_query.query.sql_code(), values).map_err(|index| SqlQueryEngineError::ErrorMissingParam {
+        item_key: dynamic_query.query.item_key.clone(),
+        param_name: format!("${}", index),
+    })
+}
+
+/// Remplace les placeholders `$n` par `?`, hors chaînes (`'...'`, `"..."`), identifiants (`` `...` ``)
+/// et commentaires (`--`, `#`, `/* */`), et renvoie les numéros des placeholders dans l'ordre du texte.
+pub(crate) fn question_mark_placeholders(sql: &str) -> (String, Vec<usize>)
+{
+    let mut translated = String::with_capacity(sql.len());
+    let mut order = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    let mut previous = None;
+
+    while let Some((start, c)) = chars.next()
+    {
+        match c
+        {
+            '\'' | '"' | '`' => {
+                translated.push(c);
+                while let Some((_, next)) = chars.next() {
+                    translated.push(next);
+                    if next == '\\' && c != '`' {
+                        if let Some((_, escaped)) = chars.next() {
+                            translated.push(escaped);
+                        }
+                    } else if next == c {
+                        break;
+                    }
+                }
+            },
+            '#' | '-' | '/' if is_comment_start(c, &sql[start..]) => {
+                let end = match c {
+                    '/' => sql[start + 2..].find("*/").map(|i| start + 2 + i + 2),
+                    _ => sql[start..].find('\n').map(|i| start + i),
+                }.unwrap_or(sql.len());
+                translated.push_str(&sql[start..end]);
+                while chars.peek().is_some_and(|(i, _)| *i < end) {
+                    chars.next();
+                }
+            },
+            '$' if !previous.is_some_and(|p: char| p.is_alphanumeric() || p == '_') => {
+                let digits: String = sql[start + 1..].chars().take_while(char::is_ascii_digit).collect();
+                match digits.parse::<usize>() {
+                    Ok(index) if index > 0 => {
+                        translated.push('?');
+                        order.push(index);
+                        for _ in 0..digits.len() {
+                            chars.next();
+                        }
+                    },
+                    _ => translated.push(c),
+                }
+            },
+            _ => translated.push(c),
+        }
+        previous = Some(c);
+    }
+
+    (translated, order)
+}
+
+fn is_comment_start(c: char, rest: &str) -> bool
+{
+    match c {
+        '#' => true,
+        '-' => rest.starts_with("--"),
+        '/' => rest.starts_with("/*"),
+        _ => false,
+    }
+}
+
+/// Récupère les lignes de la requête, en refusant plus de `max_rows` lignes si une limite est donnée.
+async fn fetch_rows<'q, 'c, DB, T, E>(
+    dynamic_query: &SqlDynamicQuery,
+    query: QueryAs<'q, DB, T, <DB as Database>::Arguments<'q>>,
+    executor: E,
+    max_rows: Option<usize>,
+) -> Result<Vec<T>, SqlQueryEngineError>
+where
+    DB: SqlBackend,
+    <DB as Database>::Arguments<'q>: IntoArguments<'q, DB>,
+    T: for<'r> FromRow<'r, DB::Row> + Send + Unpin,
+    E: sqlx::Executor<'c, Database = DB>,
+    'q: 'c,
+{
+    let error = |source| dynamic_query.execution_error::<DB>(source);
+
+    match max_rows
+    {
+        None => query.fetch_all(executor).await.map_err(error),
+        Some(max_rows) =>
+        {
+            let mut rows = Vec::new();
+            let mut stream = query.fetch(executor);
+
+            while let Some(row) = stream.try_next().await.map_err(error)?
+            {
+                if rows.len() == max_rows
+                {
+                    return Err(SqlQueryEngineError::ErrorRowLimitExceeded {
+                        item_key: dynamic_query.query.item_key.clone(),
+                        max_rows,
+                    });
+                }
+                rows.push(row);
+            }
+            Ok(rows)
+        },
+    }
+}
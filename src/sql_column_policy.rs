@@ -0,0 +1,163 @@
+use std::fmt;
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::sql_query_param::SqlQueryParam;
+
+/// Politique de masquage d'une colonne du résultat, stockée en JSON (liste de politiques)
+/// dans la colonne `column_policies` de la table des requêtes.
+///
+/// La politique s'applique à tout appelant ne possédant aucun des rôles `unmasked_roles`.
+/// # Exemple
+/// ```json
+/// [
+///   { "column": "email", "action": "mask", "keep_last": 4, "unmasked_roles": ["rh"] },
+///   { "column": "numero_secu", "action": "hash", "unmasked_roles": ["rh"] },
+///   { "column": "nom", "action": "truncate", "length": 1 },
+///   { "column": "salaire", "action": "drop", "unmasked_roles": ["rh", "direction"] }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SqlColumnPolicy
+{
+    /// Nom de la colonne du résultat
+    pub column: String,
+
+    /// Traitement appliqué à la valeur
+    #[serde(flatten)]
+    pub action: SqlColumnAction,
+
+    /// Rôles voyant la valeur d'origine
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmasked_roles: Vec<String>,
+}
+
+/// Traitement appliqué à la valeur d'une colonne masquée.
+/// Les valeurs `null` sont conservées, les autres sont traitées sous forme de texte.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SqlColumnAction
+{
+    /// Remplace chaque caractère par `*`, en conservant éventuellement les `keep_last` derniers
+    Mask {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keep_last: Option<usize>,
+    },
+
+    /// Remplace la valeur par son empreinte HMAC-SHA256 (hexadécimal) calculée avec la clé secrète
+    /// du gestionnaire (voir `SqlHashKey`). L'empreinte d'une valeur reste la même tant que la clé ne change pas,
+    /// ce qui permet encore de regrouper ou de joindre les lignes sans révéler la valeur ;
+    /// sans la clé, elle ne peut pas être retrouvée par dictionnaire (emails, numéros...).
+    Hash,
+
+    /// Conserve les `length` premiers caractères
+    Truncate { length: usize },
+
+    /// Retire la colonne du résultat
+    Drop,
+}
+
+impl SqlColumnPolicy {
+    /// Indique si la politique s'applique à un appelant possédant ces rôles.
+    /// # Arguments
+    /// * `roles`: Rôles ou permissions de l'appelant
+    pub fn applies_to(&self, roles: &[String]) -> bool {
+        !self.unmasked_roles.iter().any(|role| roles.contains(role))
+    }
+
+    /// Applique la politique à une ligne (objet JSON).
+    /// Sans clé, une valeur à remplacer par son empreinte (`hash`) est remplacée par `***`.
+    pub fn apply(&self, row: &mut Value, hash_key: Option<&SqlHashKey>) {
+        let Some(object) = row.as_object_mut() else {
+            return;
+        };
+
+        if let SqlColumnAction::Drop = self.action {
+            object.remove(&self.column);
+            return;
+        }
+
+        if let Some(value) = object.get_mut(&self.column) {
+            if !value.is_null() {
+                *value = Value::String(self.action.transform(&text_of(value), hash_key));
+            }
+        }
+    }
+}
+
+/// Clé secrète des empreintes HMAC-SHA256 des colonnes masquées par `SqlColumnAction::Hash`
+/// (voir `SqlQueryManagerBuilder::hash_key`). Son `Debug` n'affiche pas la clé.
+#[derive(Clone)]
+pub struct SqlHashKey(Arc<[u8]>);
+
+impl SqlHashKey {
+    /// Crée une clé à partir d'un secret (au moins 32 octets aléatoires conseillés).
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self(Arc::from(secret.as_ref()))
+    }
+
+    /// Empreinte HMAC-SHA256 (hexadécimal) d'un texte avec cette clé.
+    pub fn hash(&self, text: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+        mac.update(text.as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+}
+
+impl fmt::Debug for SqlHashKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SqlHashKey(***)")
+    }
+}
+
+impl SqlColumnAction {
+    fn transform(&self, text: &str, hash_key: Option<&SqlHashKey>) -> String {
+        match self {
+            SqlColumnAction::Mask { keep_last } => {
+                let length = text.chars().count();
+                let kept = keep_last.unwrap_or(0).min(length);
+                text.chars()
+                    .enumerate()
+                    .map(|(i, c)| if i < length - kept { '*' } else { c })
+                    .collect()
+            },
+            SqlColumnAction::Hash => match hash_key {
+                Some(hash_key) => hash_key.hash(text),
+                None => SqlQueryParam::REDACTED.to_string(),
+            },
+            SqlColumnAction::Truncate { length } => text.chars().take(*length).collect(),
+            SqlColumnAction::Drop => String::new(),
+        }
+    }
+}
+
+/// Applique aux lignes les politiques concernant un appelant possédant ces rôles.
+/// # Arguments
+/// * `policies`: Politiques de la requête
+/// * `roles`: Rôles ou permissions de l'appelant
+/// * `hash_key`: Clé des empreintes `hash` (voir `SqlColumnPolicy::apply`)
+/// * `rows`: Lignes du résultat (objets JSON)
+pub fn apply_column_policies(policies: &[SqlColumnPolicy], roles: &[String], hash_key: Option<&SqlHashKey>, rows: &mut [Value])
+{
+    let active: Vec<&SqlColumnPolicy> = policies.iter().filter(|policy| policy.applies_to(roles)).collect();
+    if active.is_empty() {
+        return;
+    }
+
+    for row in rows.iter_mut() {
+        for policy in &active {
+            policy.apply(row, hash_key);
+        }
+    }
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
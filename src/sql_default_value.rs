@@ -0,0 +1,128 @@
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, TimeDelta};
+use crate::sql_execution_context::ExecutionContext;
+use crate::sql_query_param_type::SqlQueryParamType;
+
+/// Résout la valeur par défaut d'un paramètre.
+///
+/// `default_value` peut être une valeur littérale (liée telle quelle) ou une expression :
+/// - `today`, `now` : date du jour, date et heure courantes
+/// - `today-30d`, `today+1w`, `now-2h`, `today-1m`, `today+1y` : décalage en
+///   jours (`d`), semaines (`w`), mois (`m`), années (`y`), heures (`h`) ou minutes (`min`)
+/// - `start_of_week`, `start_of_month`, `end_of_month`, `start_of_year`, `end_of_year`
+/// - `${context.<clé>}` : valeur du contexte d'exécution (ex : `${context.user_id}`)
+///
+/// Les expressions temporelles ne sont interprétées que pour les paramètres `DATE` et `DATETIME`,
+/// formatées selon le type déclaré (`YYYY-MM-DD` ou `YYYY-MM-DD HH:MM:SS`) ; pour les autres types,
+/// `today` ou `now` restent des valeurs littérales.
+///
+/// # Arguments
+/// * `expression` - La valeur par défaut déclarée
+/// * `param_type` - Le type déclaré du paramètre
+/// * `context` - Le contexte d'exécution fourni par l'appelant
+/// * `now` - La date et l'heure de référence
+///
+/// # Returns
+/// * `Err(String)` - Si l'expression est invalide ou fait référence à une valeur absente du contexte
+pub fn resolve_default_value(
+    expression: &str,
+    param_type: &SqlQueryParamType,
+    context: &ExecutionContext,
+    now: NaiveDateTime,
+) -> Result<String, String>
+{
+    let trimmed = expression.trim();
+
+    // Valeur issue du contexte d'exécution
+    if let Some(inner) = trimmed.strip_prefix("${").and_then(|rest| rest.strip_suffix('}')) {
+        let key = inner.trim().strip_prefix("context.")
+            .ok_or_else(|| format!("unknown placeholder '{}' (expected ${{context.<key>}})", trimmed))?;
+
+        return context.get(key)
+            .ok_or_else(|| format!("execution context has no value for '{}'", key));
+    }
+
+    if !matches!(param_type, SqlQueryParamType::NaiveDate | SqlQueryParamType::NaiveDateTime) {
+        return Ok(expression.to_string());
+    }
+
+    match resolve_datetime(&trimmed.to_lowercase(), now)? {
+        Some(datetime) => Ok(format_datetime(datetime, param_type)),
+        // Valeur littérale
+        None => Ok(expression.to_string()),
+    }
+}
+
+/// Interprète une expression temporelle, `None` si l'expression n'est pas temporelle.
+fn resolve_datetime(expression: &str, now: NaiveDateTime) -> Result<Option<NaiveDateTime>, String>
+{
+    let today = now.date().and_hms_opt(0, 0, 0).unwrap_or(now);
+
+    let base = match expression {
+        "start_of_week" => return Ok(TimeDelta::try_days(now.weekday().num_days_from_monday() as i64)
+            .and_then(|days| today.checked_sub_signed(days))),
+        "start_of_month" => return Ok(first_day_of_month(now.year(), now.month()).and_then(|d| d.and_hms_opt(0, 0, 0))),
+        "end_of_month" => return Ok(first_day_of_month(now.year(), now.month())
+            .and_then(|d| d.checked_add_months(Months::new(1)))
+            .and_then(|d| d.pred_opt())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))),
+        "start_of_year" => return Ok(NaiveDate::from_ymd_opt(now.year(), 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0))),
+        "end_of_year" => return Ok(NaiveDate::from_ymd_opt(now.year(), 12, 31).and_then(|d| d.and_hms_opt(0, 0, 0))),
+        _ if expression.starts_with("today") => today,
+        _ if expression.starts_with("now") => now,
+        _ => return Ok(None),
+    };
+
+    let offset = expression.trim_start_matches("today").trim_start_matches("now").trim();
+    if offset.is_empty() {
+        return Ok(Some(base));
+    }
+
+    // Décalage : signe, nombre, unité
+    let (sign, rest) = match offset.chars().next() {
+        Some('+') => (1, &offset[1..]),
+        Some('-') => (-1, &offset[1..]),
+        // Ex : "todayx" ou "nowhere" : valeur littérale
+        _ => return Ok(None),
+    };
+    let rest = rest.trim();
+    let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    // Pas de nombre après le signe (ex : "now-playing") : valeur littérale
+    let amount = match rest[..digits_end].parse::<i64>() {
+        Ok(amount) => sign * amount,
+        Err(_) => return Ok(None),
+    };
+
+    // Les constructeurs `try_*` refusent les durées hors limites au lieu de paniquer
+    let shift = |delta: Option<TimeDelta>| delta.and_then(|delta| base.checked_add_signed(delta));
+    let shifted = match rest[digits_end..].trim() {
+        "d" => shift(TimeDelta::try_days(amount)),
+        "w" => shift(TimeDelta::try_weeks(amount)),
+        "h" => shift(TimeDelta::try_hours(amount)),
+        "min" => shift(TimeDelta::try_minutes(amount)),
+        "m" => shift_months(base, amount),
+        "y" => shift_months(base, amount.saturating_mul(12)),
+        unit => return Err(format!("unknown unit '{}' in default value '{}' (expected d, w, m, y, h or min)", unit, expression)),
+    };
+
+    shifted.map(Some).ok_or_else(|| format!("default value '{}' is out of range", expression))
+}
+
+fn first_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+fn shift_months(datetime: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        datetime.checked_add_months(count)
+    } else {
+        datetime.checked_sub_months(count)
+    }
+}
+
+fn format_datetime(datetime: NaiveDateTime, param_type: &SqlQueryParamType) -> String {
+    match param_type {
+        SqlQueryParamType::NaiveDateTime => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        _ => datetime.format("%Y-%m-%d").to_string(),
+    }
+}
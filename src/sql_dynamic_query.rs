@@ -1,7 +1,15 @@
-use regex::Regex;
-use sqlx::types::chrono;
-use sqlx::{FromRow, PgPool};
+use chrono::NaiveDateTime;
+use sqlx::{FromRow, PgPool, Pool, Postgres};
+use crate::sql_audit::SqlAuditor;
+use crate::sql_backend::{SqlBackend, SqlBindValue};
+use crate::sql_column_policy::{apply_column_policies, SqlHashKey};
+use crate::sql_json_row::SqlJsonRow;
+use crate::sql_param_validation_report::{SqlParamIssue, SqlParamValidationReport};
+use crate::sql_default_value::resolve_default_value;
+use crate::sql_execution_context::ExecutionContext;
 use crate::sql_query::SqlQuery;
+use crate::sql_query_rule::{SqlRuleValues, SqlTypedValue};
+use crate::sql_query_limits::SqlQueryLimits;
 use crate::sql_query_param::SqlQueryParam;
 use crate::sql_query_engine_error::SqlQueryEngineError;
 use crate::sql_dynamic_query_data::SqlDynamicQueryData;
@@ -25,159 +33,274 @@ impl SqlDynamicQuery
     /// 
     /// # Arguments
     /// * `dynamic_query_data` - Les données contenant les paramètres à valider
+    /// * `context` - Le contexte d'exécution, utilisé pour résoudre les valeurs par défaut évaluées par les règles
     /// 
     /// # Returns
     /// * `Ok(())` - Si tous les paramètres sont valides
-    /// * `Err(SqlQueryEngineError::ErrorCheckParams)` - Si la validation échoue
+    /// * `Err(SqlQueryEngineError::ErrorMissingParam)` - Si un paramètre requis est absent
+    /// * `Err(SqlQueryEngineError::ErrorUnexpectedParam)` - Si un paramètre fourni n'est pas déclaré
+    /// * `Err(SqlQueryEngineError::ErrorInvalidParam)` - Si une valeur ne correspond pas au type déclaré
+    /// * `Err(SqlQueryEngineError::ErrorUnknownParamType)` - Si le type déclaré n'est pas pris en charge
+    /// * `Err(SqlQueryEngineError::ErrorConstraintViolation)` - Si une valeur ne respecte pas une contrainte déclarée
+    /// * `Err(SqlQueryEngineError::ErrorRuleViolation)` - Si une règle portant sur plusieurs paramètres n'est pas respectée
     /// 
     /// # Validations effectuées
     /// - Vérification que tous les paramètres requis sont présents
     /// - Validation du type de chaque paramètre
+    /// - Validation des contraintes déclarées (`SqlParamConstraints`)
+    /// - Évaluation des règles de la requête (`SqlQueryRule`), après les vérifications par paramètre
     /// - Vérification qu'aucun paramètre superflu n'est fourni
-    
-    pub fn check_query_params(&self, dynamic_query_data: &SqlDynamicQueryData) -> Result<(), SqlQueryEngineError>
+    pub fn check_query_params(&self, dynamic_query_data: &SqlDynamicQueryData, context: &ExecutionContext) -> Result<(), SqlQueryEngineError>
     {
+        self.validate_query_params(dynamic_query_data, context).into_first_error()
+    }
 
-        // Si la requête n'a pas de paramètres définis
-        let query_params = match &self.params {
-            Some(params) => params,
-            None => {
-                // Si aucun paramètre n'est défini mais que des paramètres sont fournis
-                if !dynamic_query_data.params.is_empty() {
-                    return Err(SqlQueryEngineError::ErrorCheckParams(
-                        format!("Query '{}' expects no parameters, but {} parameters were provided", 
-                            self.query.item_key, dynamic_query_data.params.len())
-                    ));
-                }
-                return Ok(());
-            }
-        };
+    /// Vérifie tous les paramètres fournis sans s'arrêter au premier problème
+    ///
+    /// # Arguments
+    /// * `dynamic_query_data` - Les données contenant les paramètres à valider
+    /// * `context` - Le contexte d'exécution : les règles portent sur les valeurs qui seront liées,
+    ///   valeurs par défaut comprises (résolues comme à l'exécution, voir `resolve_default_value`)
+    ///
+    /// # Returns
+    /// Un `SqlParamValidationReport` listant chaque problème détecté par paramètre
+    /// (paramètre requis absent, paramètre inconnu, type invalide, contrainte ou règle non respectée).
+    /// Le rapport est vide si tout est valide.
+    pub fn validate_query_params(&self, dynamic_query_data: &SqlDynamicQueryData, context: &ExecutionContext) -> SqlParamValidationReport
+    {
+        self.validate_query_params_at(dynamic_query_data, context, chrono::Local::now().naive_local())
+    }
+
+    /// Identique à `validate_query_params`, les valeurs par défaut relatives (`today`, `now`)
+    /// étant résolues par rapport à `now`.
+    fn validate_query_params_at(
+        &self,
+        dynamic_query_data: &SqlDynamicQueryData,
+        context: &ExecutionContext,
+        now: NaiveDateTime,
+    ) -> SqlParamValidationReport
+    {
+        let mut report = SqlParamValidationReport::new(self.query.item_key.clone());
+        let query_params = self.params.as_deref().unwrap_or_default();
 
         // Vérifier que tous les paramètres requis sont présents
         for query_param in query_params {
-            if query_param.is_required == 1
+            if query_param.is_required == 1 && dynamic_query_data.get_param(&query_param.param_name).is_none()
             {
-                let param_found = dynamic_query_data.params.iter()
-                    .any(|(name, _)| name == &query_param.param_name);
-                
-                if !param_found {
-                    return Err(SqlQueryEngineError::ErrorCheckParams(
-                        format!("Required parameter '{}' is missing for query '{}'", 
-                            query_param.param_name, self.query.item_key)
-                    ));
-                }
+                report.push(query_param.param_name.clone(), SqlParamIssue::Missing);
             }
         }
 
         // Vérifier que tous les paramètres fournis sont attendus et ont le bon type
-        for (param_name, param_value) in &dynamic_query_data.params
+        // (dans l'ordre des noms, pour que le rapport soit déterministe)
+        let mut provided: Vec<(&String, &String)> = dynamic_query_data.params.iter().collect();
+        provided.sort_by_key(|(name, _)| *name);
+
+        for (param_name, param_value) in provided
         {
-            let query_param = query_params.iter()
-                .find(|p| &p.param_name == param_name);
-            
-            let query_param = match query_param {
+            let query_param = match query_params.iter().find(|p| &p.param_name == param_name) {
                 Some(param) => param,
                 None => {
-                    return Err(SqlQueryEngineError::ErrorCheckParams(
-                        format!("Unexpected parameter '{}' provided for query '{}'", 
-                            param_name, self.query.item_key)
-                    ));
+                    report.push(param_name.clone(), SqlParamIssue::Unexpected);
+                    continue;
                 }
             };
 
             // Valider le type du paramètre
-            if let Err(validation_error) = Self::validate_param_type(&query_param.param_type, param_value) {
-                return Err(SqlQueryEngineError::ErrorCheckParams(
-                    format!("Parameter '{}' validation failed for query '{}': {}", 
-                        param_name, self.query.item_key, validation_error)
-                ));
+            match query_param.param_type.parse::<SqlQueryParamType>() {
+                Ok(param_type) => {
+                    if !Self::validate_param_type(&param_type, param_value) {
+                        report.push(param_name.clone(), SqlParamIssue::InvalidType {
+                            expected_type: query_param.param_type.clone(),
+                            value: query_param.display_value(param_value),
+                        });
+                        continue;
+                    }
+
+                    // Valider les contraintes déclarées
+                    if let Some(constraints) = &query_param.constraints {
+                        for violation in constraints.check(&param_type, param_value) {
+                            report.push(param_name.clone(), SqlParamIssue::ConstraintViolation {
+                                value: query_param.display_value(param_value),
+                                violation,
+                            });
+                        }
+                    }
+                },
+                Err(param_type) => report.push(param_name.clone(), SqlParamIssue::UnknownType { param_type }),
             }
         }
 
-        Ok(())
+        // Évaluer les règles portant sur plusieurs paramètres
+        let values = RuleValues { dynamic_query: self, dynamic_query_data, context, now };
+        for rule in self.query.rules() {
+            if !rule.is_satisfied(&values) {
+                report.push(rule.target_param(), SqlParamIssue::RuleViolation { rule: rule.clone() });
+            }
+        }
+
+        report
+    }
+
+    /// Déclare dans `dynamic_query_data` les paramètres sensibles de la requête,
+    /// pour que leurs valeurs soient masquées dans les traces d'audit.
+    ///
+    /// # Arguments
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    pub fn mark_sensitive_params(&self, dynamic_query_data: &mut SqlDynamicQueryData)
+    {
+        for param in self.params.as_deref().unwrap_or_default() {
+            if param.is_sensitive() {
+                dynamic_query_data.mark_sensitive(param.param_name.clone());
+            }
+        }
     }
 
     /// Valide qu'une valeur correspond au type attendu
     /// 
     /// # Arguments
-    /// * `expected_type` - Le type attendu (String, Integer, Float, Boolean, DateTime)
+    /// * `param_type` - Le type attendu
     /// * `value` - La valeur à valider
     /// 
     /// # Returns
-    /// * `Ok(())` - Si la valeur correspond au type
-    /// * `Err(String)` - Message d'erreur si la validation échoue
-    fn validate_param_type(expected_type: &str, value: &str) -> Result<(), String> {
-        // Convertir le type string en enum pour une validation plus robuste
-        let param_type = match SqlQueryParamType::from(expected_type.to_string())
+    /// * `true` - Si la valeur correspond au type
+    pub(crate) fn validate_param_type(param_type: &SqlQueryParamType, value: &str) -> bool {
+        match param_type {
+            // Toute chaîne est valide pour le type String
+            SqlQueryParamType::String => true,
+            SqlQueryParamType::I32 => value.parse::<i32>().is_ok(),
+            SqlQueryParamType::F64 => value.parse::<f64>().is_ok(),
+            SqlQueryParamType::Bool => Self::parse_bool(value).is_some(),
+            // Format SQL standard : 2023-12-25 10:30:00
+            SqlQueryParamType::NaiveDateTime => chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").is_ok(),
+            // Format SQL standard : 2023-12-25
+            SqlQueryParamType::NaiveDate => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        }
+    }
+
+    /// Interprète une valeur booléenne (true/false, 1/0, yes/no, on/off)
+    fn parse_bool(value: &str) -> Option<bool> {
+        match value.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(true),
+            "false" | "0" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Convertit le type déclaré d'un paramètre
+    fn param_type(&self, param: &SqlQueryParam) -> Result<SqlQueryParamType, SqlQueryEngineError> {
+        param.param_type.parse().map_err(|param_type| SqlQueryEngineError::ErrorUnknownParamType {
+            item_key: self.query.item_key.clone(),
+            param_name: param.param_name.clone(),
+            param_type,
+        })
+    }
+
+    /// Résout la valeur de chaque paramètre déclaré, dans l'ordre des paramètres :
+    /// la valeur fournie, sinon la valeur par défaut résolue avec le contexte d'exécution.
+    /// Une valeur par défaut résolue doit correspondre au type déclaré.
+    ///
+    /// # Arguments
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `context` - Le contexte d'exécution fourni par l'appelant
+    /// * `now` - Instant de référence des valeurs par défaut relatives (`today`, `now`)
+    pub(crate) fn resolve_param_values<'a>(
+        &'a self,
+        dynamic_query_data: &SqlDynamicQueryData,
+        context: &ExecutionContext,
+        now: NaiveDateTime,
+    ) -> Result<Vec<ResolvedParam<'a>>, SqlQueryEngineError>
+    {
+        let mut resolved = Vec::new();
+
+        for param in self.params.as_deref().unwrap_or_default()
         {
-            param_type => param_type,
-        };
+            let param_type = self.param_type(param)?;
 
-        match param_type {
-            SqlQueryParamType::String => {
-                // Toute chaîne est valide pour le type String
-                Ok(())
-            },
-            SqlQueryParamType::I32 => {
-                value.parse::<i32>()
-                    .map(|_| ())
-                    .map_err(|_| format!("'{}' is not a valid integer", value))
-            },
-            SqlQueryParamType::F64 => {
-                value.parse::<f64>()
-                    .map(|_| ())
-                    .map_err(|_| format!("'{}' is not a valid float", value))
-            },
-            SqlQueryParamType::Bool => {
-                match value.to_lowercase().as_str() {
-                    "true" | "false" | "1" | "0" | "yes" | "no" | "on" | "off" => Ok(()),
-                    _ => Err(format!("'{}' is not a valid boolean (expected: true/false, 1/0, yes/no, on/off)", value))
-                }
-            },
-            SqlQueryParamType::NaiveDateTime => {
-                // Validation basique pour les formats de date/heure courants
-                // On peut utiliser chrono pour une validation plus robuste si nécessaire
-                if value.is_empty() {
-                    return Err("DateTime cannot be empty".to_string());
-                }
-                
-                // Patterns basiques pour ISO 8601, formats SQL standards
-                // SQL standard: 2023-12-25 10:30:00
-                let datetime_pattern = r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}$";
-
-                // ISO 8601: 2023-12-25T10:30:00Z
-                // r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z?$",
-                
-                // Date seule: 2023-12-25
-                // r"^\d{4}-\d{2}-\d{2}$",
-                
-                let is_valid = regex::Regex::new(datetime_pattern)
-                        .map(|re| re.is_match(value))
-                        .unwrap_or(false);
-                
-                if is_valid {Ok(())}
-                else {Err(format!("'{}' is not a valid datetime format (expected: YYYY-MM-DD, YYYY-MM-DD HH:MM:SS, or ISO 8601)", value))}
-            },
-            SqlQueryParamType::NaiveDate => {
-                // Validation basique pour les dates
-                // On peut utiliser chrono pour une validation plus robuste si nécessaire
-                if value.is_empty() {
-                    return Err("Date cannot be empty".to_string());
-                }
-                
-                // Pattern basique pour le format SQL standard: 2023-12-25
-                let date_pattern = r"^\d{4}-\d{2}-\d{2}$";
-                
-                let is_valid = regex::Regex::new(date_pattern)
-                        .map(|re| re.is_match(value))
-                        .unwrap_or(false);
-                
-                if is_valid {Ok(())}
-                else {Err(format!("'{}' is not a valid date format (expected: YYYY-MM-DD)", value))}
-            },
+            let value = match (dynamic_query_data.get_param(&param.param_name), &param.default_value)
+            {
+                (Some(value), _) => value.clone(),
+                (None, Some(default)) =>
+                {
+                    let value = resolve_default_value(default, &param_type, context, now)
+                        .map_err(|reason| self.invalid_default_value(param, default, reason))?;
+
+                    if !Self::validate_param_type(&param_type, &value) {
+                        return Err(self.invalid_default_value(
+                            param,
+                            default,
+                            format!("resolved value '{}' is not a valid {}", param.display_value(&value), param.param_type),
+                        ));
+                    }
+                    value
+                },
+                (None, None) => return Err(SqlQueryEngineError::ErrorMissingParam {
+                    item_key: self.query.item_key.clone(),
+                    param_name: param.param_name.clone(),
+                }),
+            };
+
+            resolved.push(ResolvedParam { param, param_type, value });
+        }
+
+        Ok(resolved)
+    }
+
+    fn invalid_default_value(&self, param: &SqlQueryParam, default_value: &str, reason: String) -> SqlQueryEngineError {
+        SqlQueryEngineError::ErrorInvalidDefaultValue {
+            item_key: self.query.item_key.clone(),
+            param_name: param.param_name.clone(),
+            default_value: param.display_value(default_value),
+            reason,
+        }
+    }
+
+    fn invalid_param(&self, param: &SqlQueryParam, value: &str) -> SqlQueryEngineError {
+        SqlQueryEngineError::ErrorInvalidParam {
+            item_key: self.query.item_key.clone(),
+            param_name: param.param_name.clone(),
+            expected_type: param.param_type.clone(),
+            value: param.display_value(value),
+        }
+    }
+
+    /// Convertit la valeur résolue de chaque paramètre selon son type déclaré, dans l'ordre des paramètres.
+    pub(crate) fn bind_values(
+        &self,
+        dynamic_query_data: &SqlDynamicQueryData,
+        context: &ExecutionContext,
+        now: NaiveDateTime,
+    ) -> Result<Vec<SqlBindValue>, SqlQueryEngineError>
+    {
+        let mut values = Vec::new();
+
+        for resolved in self.resolve_param_values(dynamic_query_data, context, now)?
+        {
+            let (param, value) = (resolved.param, resolved.value.as_str());
+
+            let bind_value = match resolved.param_type
+            {
+                SqlQueryParamType::String => Some(SqlBindValue::String(value.to_string())),
+                SqlQueryParamType::I32 => value.parse::<i32>().ok().map(SqlBindValue::I32),
+                SqlQueryParamType::F64 => value.parse::<f64>().ok().map(SqlBindValue::F64),
+                SqlQueryParamType::Bool => Self::parse_bool(value).map(SqlBindValue::Bool),
+                SqlQueryParamType::NaiveDate => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+                    .map(SqlBindValue::NaiveDate),
+                SqlQueryParamType::NaiveDateTime => chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok()
+                    .map(SqlBindValue::NaiveDateTime),
+            };
+
+            values.push(bind_value.ok_or_else(|| self.invalid_param(param, value))?);
         }
+
+        Ok(values)
     }
 
+    /// Exécute la requête avec les paramètres fournis, sans limite.
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
     pub async fn execute<T>
     (
         &self,
@@ -187,105 +310,293 @@ impl SqlDynamicQuery
     where
         T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
     {
-        // Étape 1 : Vérification des paramètres
-        self.check_query_params(&dynamic_query_data)?;
+        self.execute_with_limits(pool, dynamic_query_data, SqlQueryLimits::unlimited()).await
+    }
 
-        // Étape 2 : Construction de la requête SQL dynamique
-        let mut query = sqlx::query_as::<sqlx::Postgres, T>(self.query.sql_code());
+    /// Exécute la requête avec les paramètres fournis en appliquant des limites.
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `limits` - Nombre maximal de lignes et durée maximale d'exécution
+    pub async fn execute_with_limits<T>
+    (
+        &self,
+        pool: &PgPool,
+        dynamic_query_data: SqlDynamicQueryData,
+        limits: SqlQueryLimits,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+    {
+        self.execute_with_context(pool, dynamic_query_data, &ExecutionContext::default(), limits).await
+    }
 
-        
-        if let Some(params) = &self.params
+    /// Exécute la requête avec les paramètres fournis, un contexte d'exécution et des limites.
+    /// Les valeurs par défaut des paramètres absents sont résolues avec ce contexte
+    /// (voir `resolve_default_value`). Si le contexte n'est pas vide, la requête est exécutée
+    /// dans une transaction après application des variables de session (voir `apply_session_settings`).
+    /// Si `limits.read_only` est vrai ou si la requête est déclarée en lecture seule, la transaction
+    /// est ouverte avec `START TRANSACTION READ ONLY` : toute écriture est refusée par PostgreSQL.
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `context` - Le contexte d'exécution fourni par l'appelant
+    /// * `limits` - Nombre maximal de lignes et durée maximale d'exécution
+    ///
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorExecutionQuery)` - Si la requête échoue
+    /// * `Err(SqlQueryEngineError::ErrorReadOnlyViolation)` - Si elle tente d'écrire en lecture seule
+    /// * `Err(SqlQueryEngineError::ErrorRowLimitExceeded)` - Si elle renvoie plus de `max_rows` lignes
+    /// * `Err(SqlQueryEngineError::ErrorTimeout)` - Si elle dépasse `timeout`
+    /// * `Err(SqlQueryEngineError::ErrorInvalidDefaultValue)` - Si une valeur par défaut ne peut pas être résolue
+    /// * `Err(SqlQueryEngineError::ErrorUnsafeSql)` - Si le code SQL est refusé par l'analyse de sécurité
+    pub async fn execute_with_context<T>
+    (
+        &self,
+        pool: &PgPool,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+    {
+        self.execute_on::<T, Postgres>(pool, dynamic_query_data, context, limits).await
+    }
+
+    /// Identique à `execute_with_context`, sur le pool d'un backend quelconque (voir `SqlBackend`),
+    /// par exemple un `SqlitePool`.
+    /// Comme tous les chemins d'exécution, le code SQL est d'abord analysé (voir `SqlQuery::check_safety`) :
+    /// une seule instruction, dont la nature ne dépasse ni `limits.max_statement_kind` ni la colonne
+    /// `statement_kind` de la requête.
+    /// Un résultat typé ne pouvant pas être masqué, une requête dont des colonnes doivent être masquées
+    /// pour les rôles du contexte est refusée (utiliser `execute_json_on`).
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `context` - Le contexte d'exécution fourni par l'appelant
+    /// * `limits` - Nombre maximal de lignes et durée maximale d'exécution
+    ///
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorMaskingUnsupported)` - Si des colonnes doivent être masquées pour l'appelant
+    pub async fn execute_on<T, DB>
+    (
+        &self,
+        pool: &Pool<DB>,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        DB: SqlBackend,
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        if self.query.is_masked_for(&context.roles) {
+            return Err(SqlQueryEngineError::ErrorMaskingUnsupported { item_key: self.query.item_key.clone() });
+        }
+
+        self.fetch_on::<T, DB>(pool, dynamic_query_data, context, limits).await
+    }
+
+    /// Identique à `execute_on`, sans vérifier les politiques de masquage des colonnes :
+    /// réservé aux chemins qui les appliquent eux-mêmes au résultat (voir `execute_json_on`).
+    pub(crate) async fn fetch_on<T, DB>
+    (
+        &self,
+        pool: &Pool<DB>,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        DB: SqlBackend,
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        // Étape 1 : Analyse de sécurité du code SQL, limitée par `limits` et par la nature déclarée pour la requête
+        self.query.check_safety(limits.max_statement_kind, DB::sql_dialect())?;
+
+        // Étape 2 : Vérification des paramètres, les règles portant sur les valeurs liées à l'étape 3
+        let now = chrono::Local::now().naive_local();
+        self.validate_query_params_at(&dynamic_query_data, context, now).into_first_error()?;
+
+        // Étape 3 : Conversion des valeurs selon le type déclaré
+        let values = self.bind_values(&dynamic_query_data, context, now)?;
+
+        // Étape 4 : Exécution, en lecture seule si demandé
+        let read_only = limits.read_only || self.query.is_read_only();
+        let execution = DB::fetch_dynamic::<T>(self, pool, values, context, read_only, limits.max_rows);
+
+        match limits.timeout
         {
-            // params.sort_by_key(|p| p.param_order);
-            for param in params
+            None => execution.await,
+            Some(timeout) => match tokio::time::timeout(timeout, execution).await
             {
-                let value = match dynamic_query_data.get_param(&param.param_name)
-                {
-                    Some(v) => v,
-                    None => {
-                        if let Some(default) = &param.default_value
-                        {
-                            default
-                        }
-                        else
-                        {
-                            return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                                "Parameter '{}' is missing and has no default value",
-                                param.param_name
-                            )));
-                        }
-                    }
-                };
+                Ok(result) => result,
+                Err(_) => Err(SqlQueryEngineError::ErrorTimeout {
+                    item_key: self.query.item_key.clone(),
+                    timeout,
+                }),
+            },
+        }
+    }
 
-                
-                let param_type = SqlQueryParamType::from(param.param_type.clone());
+    /// Identique à `execute_with_context`, mais renvoie chaque ligne sous forme d'objet JSON
+    /// (voir `SqlJsonRow`) après application des politiques de masquage des colonnes
+    /// concernant les rôles du contexte (voir `SqlColumnPolicy`).
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `context` - Le contexte d'exécution fourni par l'appelant
+    /// * `limits` - Nombre maximal de lignes et durée maximale d'exécution
+    /// * `hash_key` - Clé des empreintes des colonnes `hash` (voir `SqlHashKey`)
+    ///
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorMissingHashKey)` - Si une colonne doit être remplacée par son empreinte
+    ///   pour l'appelant et qu'aucune clé n'est fournie
+    pub async fn execute_json
+    (
+        &self,
+        pool: &PgPool,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+        hash_key: Option<&SqlHashKey>,
+    ) -> Result<Vec<serde_json::Value>, SqlQueryEngineError>
+    {
+        self.execute_json_on(pool, dynamic_query_data, context, limits, hash_key).await
+    }
 
+    /// Identique à `execute_json`, sur le pool d'un backend quelconque (voir `execute_on`).
+    pub async fn execute_json_on<DB>
+    (
+        &self,
+        pool: &Pool<DB>,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+        hash_key: Option<&SqlHashKey>,
+    ) -> Result<Vec<serde_json::Value>, SqlQueryEngineError>
+    where
+        DB: SqlBackend,
+        SqlJsonRow: for<'r> sqlx::FromRow<'r, DB::Row>,
+    {
+        if hash_key.is_none() && self.query.is_hashed_for(&context.roles) {
+            return Err(SqlQueryEngineError::ErrorMissingHashKey { item_key: self.query.item_key.clone() });
+        }
 
-                query = match param_type
-                {
-                    SqlQueryParamType::String => query.bind(value.to_string()),
-
-                    SqlQueryParamType::I32 => match value.parse::<i32>()
-                    {
-                        Ok(v) => query.bind(v),
-                        Err(_) => return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                            "Invalid integer value for '{}'",
-                            param.param_name
-                        ))),
-                    },
-
-                    SqlQueryParamType::F64 => match value.parse::<f64>()
-                    {
-                        Ok(v) => query.bind(v),
-                        Err(_) => return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                            "Invalid float value for '{}'",
-                            param.param_name
-                        ))),
-                    },
-
-                    SqlQueryParamType::Bool => match value.to_lowercase().as_str()
-                    {
-                        "true" | "1" | "yes" | "on" => query.bind(true),
-                        "false" | "0" | "no" | "off" => query.bind(false),
-                        _ => return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                            "Invalid boolean value for '{}'",
-                            param.param_name
-                        ))),
-                    },
-
-                    SqlQueryParamType::NaiveDate => match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
-                    {
-                        Ok(dt) => query.bind(dt),
-                        Err(e) => return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                            "{} || Invalid datetime format for '{}' : '{}'. Expected format: 'YYYY-MM-DD'",
-                            e,
-                            param.param_name,
-                            value
-                        ))),
-                    },
-                    SqlQueryParamType::NaiveDateTime => match chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
-                    {
-                        Ok(dt) => query.bind(dt),
-                        Err(e) => return Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                            "{} || Invalid datetime format for '{}' : '{}'. Expected format: 'YYYY-MM-DD HH:MM:SS'",
-                            e,
-                            param.param_name,
-                            value
-                        ))),
-                    },
-                };
-            }
+        let rows = self.fetch_on::<SqlJsonRow, DB>(pool, dynamic_query_data, context, limits).await?;
+        let mut rows: Vec<serde_json::Value> = rows.into_iter().map(SqlJsonRow::into_inner).collect();
+        apply_column_policies(self.query.column_policies(), &context.roles, hash_key, &mut rows);
+        Ok(rows)
+    }
+
+    /// Identique à `execute_with_context`, en traçant l'exécution (paramètres, contexte, durée,
+    /// nombre de lignes ou code d'erreur) avec l'audit donné.
+    ///
+    /// # Arguments
+    /// * `pool` - Pool de connexions sur lequel exécuter la requête
+    /// * `dynamic_query_data` - Les données contenant les paramètres de la requête
+    /// * `context` - Le contexte d'exécution fourni par l'appelant
+    /// * `limits` - Nombre maximal de lignes et durée maximale d'exécution
+    /// * `auditor` - L'audit recevant la trace
+    ///
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorAudit)` - Si la requête réussit mais que la trace ne peut pas être enregistrée
+    pub async fn execute_with_audit<T>
+    (
+        &self,
+        pool: &PgPool,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        limits: SqlQueryLimits,
+        auditor: &SqlAuditor,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+    {
+        let mut dynamic_query_data = dynamic_query_data;
+        self.mark_sensitive_params(&mut dynamic_query_data);
+
+        let audited_data = dynamic_query_data.clone();
+        auditor.audit(&audited_data, context, self.execute_with_context(pool, dynamic_query_data, context, limits)).await
+    }
+
+    /// Applique les variables de session du contexte avec `set_config(nom, valeur, true)`,
+    /// équivalent de `SET LOCAL` : elles disparaissent à la fin de la transaction
+    /// et ne peuvent pas être vues par le prochain utilisateur de la connexion.
+    ///
+    /// # Arguments
+    /// * `connection` - Connexion portant une transaction ouverte
+    /// * `settings` - Les variables à appliquer (voir `ExecutionContext::session_settings`)
+    pub async fn apply_session_settings(
+        connection: &mut sqlx::PgConnection,
+        settings: &[(String, String)],
+    ) -> Result<(), sqlx::Error>
+    {
+        for (name, value) in settings
+        {
+            sqlx::query("SELECT set_config($1, $2, true)")
+                .bind(name)
+                .bind(value)
+                .execute(&mut *connection)
+                .await?;
         }
+        Ok(())
+    }
+
+    pub(crate) fn execution_error<DB: SqlBackend>(&self, source: sqlx::Error) -> SqlQueryEngineError {
+        let item_key = self.query.item_key.clone();
 
-        // Étape 3 : Exécution
-        match query.fetch_all(pool).await {
-            Ok(result) => Ok(result),
-            Err(e) => Err(SqlQueryEngineError::ErrorExecutionQuery(format!(
-                "Error executing query '{}': {}",
-                self.query.item_key, e
-            ))),
+        if DB::is_read_only_violation(&source) {
+            return SqlQueryEngineError::ErrorReadOnlyViolation { item_key, source };
         }
+
+        SqlQueryEngineError::ErrorExecutionQuery { item_key, source }
+    }
+}
+
+/// Valeur résolue d'un paramètre, prête à être liée à la requête.
+pub(crate) struct ResolvedParam<'a>
+{
+    pub param: &'a SqlQueryParam,
+    pub param_type: SqlQueryParamType,
+    pub value: String,
+}
+
+/// Valeurs fournies (ou par défaut) pour l'évaluation des règles d'une requête,
+/// les valeurs par défaut étant résolues comme par `SqlDynamicQuery::resolve_param_values`.
+struct RuleValues<'a>
+{
+    dynamic_query: &'a SqlDynamicQuery,
+    dynamic_query_data: &'a SqlDynamicQueryData,
+    context: &'a ExecutionContext,
+    now: NaiveDateTime,
+}
+
+impl SqlRuleValues for RuleValues<'_> {
+    fn is_provided(&self, param_name: &str) -> bool {
+        self.dynamic_query_data.get_param(param_name).is_some()
+    }
+
+    fn raw(&self, param_name: &str) -> Option<&str> {
+        self.dynamic_query_data.get_param(param_name).map(String::as_str)
+    }
+
+    fn typed(&self, param_name: &str) -> Option<SqlTypedValue> {
+        let param = self.dynamic_query.params.as_deref()?
+            .iter()
+            .find(|p| p.param_name == param_name)?;
+        let param_type = param.param_type.parse::<SqlQueryParamType>().ok()?;
+
+        let value = match self.dynamic_query_data.get_param(param_name) {
+            Some(value) => value.clone(),
+            None => resolve_default_value(param.default_value.as_deref()?, &param_type, self.context, self.now).ok()?,
+        };
+
+        SqlTypedValue::parse(&param_type, &value)
     }
 }
-    
-    
@@ -1,17 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::sql_query_param::SqlQueryParam;
 
 /// Cette structure est utilisée pour représenter les données d'une requête SQL dynamique.
 /// Elle contient la clé de l'élément (`item_key`) et un ensemble de paramètres associés à cette requête.
 /// /// # Champs
 /// - `item_key`: Clé d'élément unique pour identifier la requête dynamique.
 /// - `params`: Un `HashMap` contenant les paramètres de la requête, où la clé est le nom du paramètre et la valeur est sa valeur sous forme de chaîne de caractères.
+/// - `sensitive_params`: Noms des paramètres dont la valeur est masquée dans les traces d'audit
+///   (renseignés par `SqlDynamicQuery::mark_sensitive_params` ou `mark_sensitive`).
+///
+/// Le `Debug` n'affiche jamais les valeurs des paramètres, seulement leurs noms : les données sont construites
+/// par l'appelant, qui ne sait pas forcément quels paramètres la requête déclare sensibles.
 /// 
-
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SqlDynamicQueryData
 {
     pub item_key: String,
     pub params: HashMap<String, String>,
+    pub sensitive_params: HashSet<String>,
 }
 
 impl SqlDynamicQueryData {
@@ -21,11 +29,11 @@ impl SqlDynamicQueryData {
     ///
     /// * `item_key` - Clé d'élément unique pour identifier la requête dynamique.
     /// * `params` - HashMap contenant les paramètres de la requête.
-    
     pub fn new(item_key: String, params: HashMap<String, String>) -> Self {
         SqlDynamicQueryData {
             item_key,
             params,
+            sensitive_params: HashSet::new(),
         }
     }
 
@@ -39,6 +47,7 @@ impl SqlDynamicQueryData {
         SqlDynamicQueryData {
             item_key,
             params: HashMap::new(),
+            sensitive_params: HashSet::new(),
         }
     }
 
@@ -48,7 +57,6 @@ impl SqlDynamicQueryData {
     ///
     /// * `key` - Nom du paramètre.
     /// * `value` - Valeur du paramètre.
-    
     pub fn add_param(&mut self, key: String, value: String) {
         self.params.insert(key, value);
     }
@@ -62,8 +70,49 @@ impl SqlDynamicQueryData {
     /// # Retourne
     ///
     /// Une `Option<&String>` contenant la valeur du paramètre si elle existe.
-    
     pub fn get_param(&self, key: &str) -> Option<&String> {
         self.params.get(key)
     }
-}
\ No newline at end of file
+
+    /// Déclare un paramètre comme sensible : sa valeur sera masquée.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Nom du paramètre.
+    pub fn mark_sensitive(&mut self, key: impl Into<String>) {
+        self.sensitive_params.insert(key.into());
+    }
+
+    /// Déclare tous les paramètres fournis comme sensibles, par exemple lorsque la définition
+    /// de la requête ne peut pas être lue.
+    pub fn mark_all_sensitive(&mut self) {
+        self.sensitive_params.extend(self.params.keys().cloned());
+    }
+
+    /// Indique si la valeur d'un paramètre est masquée.
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        self.sensitive_params.contains(key)
+    }
+
+    /// Paramètres triés par nom, valeurs sensibles remplacées par `***` (pour les journaux).
+    pub fn redacted_params(&self) -> BTreeMap<String, String> {
+        self.params.iter()
+            .map(|(key, value)| {
+                let value = match self.is_sensitive(key) {
+                    true => SqlQueryParam::REDACTED.to_string(),
+                    false => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for SqlDynamicQueryData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlDynamicQueryData")
+            .field("item_key", &self.item_key)
+            .field("params", &self.params.keys().collect::<BTreeSet<_>>())
+            .finish()
+    }
+}
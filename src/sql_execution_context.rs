@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+
+/// Contexte d'exécution fourni par l'appelant (utilisateur, tenant, rôles, valeurs arbitraires).
+///
+/// Les valeurs sont accessibles aux valeurs par défaut des paramètres via `${context.<clé>}`.
+/// Lorsqu'il n'est pas vide, le contexte est aussi appliqué comme variables de session
+/// (`set_config(..., true)`, équivalent de `SET LOCAL`) dans la transaction qui exécute la requête,
+/// pour les politiques RLS qui lisent `current_setting('app.user_id')` :
+/// - `<préfixe>.user_id`, `<préfixe>.tenant_id`
+/// - `<préfixe>.roles` : rôles séparés par des virgules
+/// - `<préfixe>.<clé>` pour chaque entrée de `values`
+///
+/// Le préfixe par défaut est `app`. Le préfixe et les clés de `values` doivent être des identifiants,
+/// et une clé de `values` ne peut pas remplacer `user_id`, `tenant_id` ou `roles` (voir `session_settings`).
+/// # Champs
+/// - `user_id`: Identifiant de l'utilisateur à l'origine de l'exécution (`${context.user_id}`)
+/// - `tenant_id`: Identifiant du tenant (`${context.tenant_id}`)
+/// - `roles`: Rôles de l'utilisateur (`${context.roles}`)
+/// - `values`: Valeurs supplémentaires (`${context.<clé>}`)
+/// - `setting_prefix`: Préfixe des variables de session (`app` si `None`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionContext
+{
+    pub user_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub roles: Vec<String>,
+    pub values: HashMap<String, String>,
+    pub setting_prefix: Option<String>,
+}
+
+impl ExecutionContext {
+    /// Préfixe utilisé par défaut pour les variables de session
+    pub const DEFAULT_SETTING_PREFIX: &'static str = "app";
+
+    /// Crée un contexte vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Définit l'identifiant de l'utilisateur.
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Définit l'identifiant du tenant.
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Ajoute un rôle.
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    /// Définit les rôles.
+    pub fn with_roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.roles = roles.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Ajoute une valeur au contexte.
+    pub fn with_value(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+
+    /// Définit le préfixe des variables de session (`app` par défaut).
+    pub fn with_setting_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.setting_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Récupère une valeur du contexte par sa clé (`user_id`, `tenant_id`, `roles` ou une clé de `values`).
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "user_id" => self.user_id.clone(),
+            "tenant_id" => self.tenant_id.clone(),
+            "roles" if !self.roles.is_empty() => Some(self.roles.join(",")),
+            _ => self.values.get(key).cloned(),
+        }
+    }
+
+    /// Indique si le contexte ne contient aucune valeur à appliquer en session.
+    pub fn is_empty(&self) -> bool {
+        self.user_id.is_none() && self.tenant_id.is_none() && self.roles.is_empty() && self.values.is_empty()
+    }
+
+    /// Clés réservées des variables de session, qu'une entrée de `values` ne peut pas redéfinir
+    pub const RESERVED_SETTING_KEYS: [&'static str; 3] = ["user_id", "tenant_id", "roles"];
+
+    /// Variables de session à appliquer (`nom complet -> valeur`), triées par nom.
+    ///
+    /// # Returns
+    /// * `Err(String)` - Si le préfixe ou une clé de `values` n'est pas un identifiant valide,
+    ///   si une clé de `values` reprend une clé réservée (`user_id`, `tenant_id`, `roles`),
+    ///   ou si deux clés ne diffèrent que par la casse (PostgreSQL ne distingue pas la casse des noms)
+    pub fn session_settings(&self) -> Result<Vec<(String, String)>, String> {
+        let prefix = self.setting_prefix.as_deref().unwrap_or(Self::DEFAULT_SETTING_PREFIX);
+        if !is_setting_identifier(prefix) {
+            return Err(format!("setting prefix '{}' is not a valid identifier", prefix));
+        }
+
+        let mut settings = Vec::new();
+
+        if let Some(user_id) = &self.user_id {
+            settings.push((format!("{}.user_id", prefix), user_id.clone()));
+        }
+        if let Some(tenant_id) = &self.tenant_id {
+            settings.push((format!("{}.tenant_id", prefix), tenant_id.clone()));
+        }
+        if !self.roles.is_empty() {
+            settings.push((format!("{}.roles", prefix), self.roles.join(",")));
+        }
+        for (key, value) in &self.values {
+            if !is_setting_identifier(key) {
+                return Err(format!("context key '{}' is not a valid identifier", key));
+            }
+            if Self::RESERVED_SETTING_KEYS.iter().any(|reserved| reserved.eq_ignore_ascii_case(key)) {
+                return Err(format!("context key '{}' is reserved", key));
+            }
+            settings.push((format!("{}.{}", prefix, key), value.clone()));
+        }
+
+        let mut names = HashSet::new();
+        if let Some((name, _)) = settings.iter().find(|(name, _)| !names.insert(name.to_ascii_lowercase())) {
+            return Err(format!("context key '{}' is defined more than once (names are case-insensitive)", name));
+        }
+
+        settings.sort();
+        Ok(settings)
+    }
+}
+
+/// Identifiant accepté dans un nom de variable de session : lettre ou `_`, puis lettres, chiffres ou `_`.
+fn is_setting_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use crate::sql_front_matter::SqlFrontMatterFile;
+use crate::sql_memory_query_repository::MemoryQueryRepository;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_definition::{SqlDefinitionIssue, SqlQueryDefinition};
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_safety::SqlDialect;
+
+/// Définitions de requêtes chargées depuis les fichiers d'un répertoire (voir `load_query_definitions`) :
+/// JSON (`.json`) ou YAML (`.yaml`, `.yml`) contenant une `SqlQueryDefinition` ou une liste de définitions,
+/// et fichiers `.sql` avec un en-tête (`SqlFrontMatterFile`) ; les autres fichiers sont ignorés.
+/// Les fichiers sont lus et vérifiés une seule fois, par `load` ou `reload`.
+/// Les clones partagent les mêmes définitions : un `reload` est visible de tous, y compris derrière
+/// un `Arc<dyn QueryRepository>`.
+#[derive(Debug, Clone)]
+pub struct FileQueryRepository
+{
+    dir: PathBuf,
+    dialect: SqlDialect,
+    queries: Arc<RwLock<Arc<MemoryQueryRepository>>>,
+}
+
+impl FileQueryRepository {
+    /// Charge les définitions des fichiers du répertoire (non récursif, par ordre de nom de fichier).
+    /// # Arguments
+    /// * `dir`: Répertoire contenant les fichiers de définitions
+    /// * `dialect`: Dialecte de la base qui exécute les requêtes, utilisé pour analyser leur code SQL
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorLoadQueryDefinitions)`: Si un fichier est illisible ou une définition invalide,
+    ///   ou si une item_key est définie plusieurs fois
+    pub fn load(dir: impl AsRef<Path>, dialect: SqlDialect) -> Result<Self, SqlQueryEngineError> {
+        let dir = dir.as_ref().to_path_buf();
+        let queries = load_dir(&dir, dialect)?;
+        Ok(Self { dir, dialect, queries: Arc::new(RwLock::new(Arc::new(queries))) })
+    }
+
+    /// Relit les fichiers du répertoire. En cas d'erreur, les définitions précédentes sont conservées.
+    /// Les lectures en cours se terminent sur les définitions qu'elles ont commencé à utiliser.
+    pub fn reload(&self) -> Result<(), SqlQueryEngineError> {
+        let queries = Arc::new(load_dir(&self.dir, self.dialect)?);
+        *self.queries.write().unwrap_or_else(|e| e.into_inner()) = queries;
+        Ok(())
+    }
+
+    /// Définitions actuellement chargées.
+    fn queries(&self) -> Arc<MemoryQueryRepository> {
+        self.queries.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Retourne le répertoire des fichiers de définitions.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Charge et vérifie les définitions des fichiers `.json`, `.yaml`, `.yml` et `.sql` d'un répertoire
+/// (non récursif, par ordre de nom de fichier), sans s'arrêter au premier fichier invalide.
+/// Les fichiers `.sql` portent leur définition dans un en-tête en commentaires (voir `SqlFrontMatterFile`).
+/// # Arguments
+/// * `dir`: Répertoire contenant les fichiers de définitions
+/// * `dialect`: Dialecte dans lequel le code SQL des définitions est analysé
+/// # Returns
+/// * `Ok(definitions)`: Les définitions, utilisables avec `SqlQueryDefinition::to_parts`
+/// * `Err(SqlQueryEngineError::ErrorLoadQueryDefinitions)`: Tous les problèmes détectés (fichier illisible,
+///   syntaxe, champ inconnu, définition invalide, item_key définie plusieurs fois), avec fichier et ligne
+pub fn load_query_definitions(dir: impl AsRef<Path>, dialect: SqlDialect) -> Result<Vec<SqlQueryDefinition>, SqlQueryEngineError> {
+    let dir = dir.as_ref();
+    let load_error = |message: std::io::Error| SqlQueryEngineError::ErrorLoadQueryDefinitions {
+        issues: vec![SqlDefinitionIssue::file(dir.display().to_string(), message)],
+    };
+
+    let mut paths = fs::read_dir(dir)
+        .map_err(load_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(load_error)?;
+    paths.sort();
+
+    let mut definitions: Vec<SqlQueryDefinition> = Vec::new();
+    let mut origins: HashMap<String, String> = HashMap::new();
+    let mut issues = Vec::new();
+    for path in paths.iter().filter(|path| path.is_file()) {
+        let display = path.display().to_string();
+        let with_path = |issue: SqlDefinitionIssue| SqlDefinitionIssue { path: display.clone(), ..issue };
+
+        match parse_file(path, dialect) {
+            Ok(parsed) => {
+                for ParsedDefinition { definition, issues: definition_issues, item_key_line } in parsed {
+                    issues.extend(definition_issues.into_iter().map(with_path));
+                    if let Some(origin) = origins.get(&definition.item_key) {
+                        issues.push(with_path(SqlDefinitionIssue {
+                            line: item_key_line,
+                            item_key: definition.item_key.clone(),
+                            field: Some("item_key".to_string()),
+                            ..SqlDefinitionIssue::file(String::new(), format!("item_key is already defined in '{}'", origin))
+                        }));
+                        continue;
+                    }
+                    origins.insert(definition.item_key.clone(), display.clone());
+                    definitions.push(definition);
+                }
+            },
+            Err(issue) => issues.push(with_path(issue)),
+        }
+    }
+
+    match issues.is_empty() {
+        true => Ok(definitions),
+        false => Err(SqlQueryEngineError::ErrorLoadQueryDefinitions { issues }),
+    }
+}
+
+fn load_dir(dir: &Path, dialect: SqlDialect) -> Result<MemoryQueryRepository, SqlQueryEngineError> {
+    load_query_definitions(dir, dialect).map(MemoryQueryRepository::from_definitions)
+}
+
+/// Définition lue dans un fichier, avec les problèmes détectés par sa vérification.
+struct ParsedDefinition
+{
+    definition: SqlQueryDefinition,
+    issues: Vec<SqlDefinitionIssue>,
+    /// Ligne de l'item_key, si elle est connue
+    item_key_line: Option<usize>,
+}
+
+/// Lit un fichier de définitions et vérifie chaque définition, `vec![]` si son extension n'est pas prise en charge.
+fn parse_file(path: &Path, dialect: SqlDialect) -> Result<Vec<ParsedDefinition>, SqlDefinitionIssue> {
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    if !matches!(extension, "json" | "yaml" | "yml" | "sql") {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| SqlDefinitionIssue::file(String::new(), e))?;
+    let definitions: Vec<SqlQueryDefinition> = match extension {
+        "sql" => {
+            let file = SqlFrontMatterFile::parse(&content)?;
+            return Ok(vec![ParsedDefinition {
+                issues: file.validate(dialect),
+                item_key_line: Some(file.line_of(Some("item_key"))),
+                definition: file.into_definition(),
+            }]);
+        },
+        "json" => {
+            let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| issue_at(Some(e.line()), e))?;
+            match value.is_array() {
+                true => serde_json::from_value(value),
+                false => serde_json::from_value(value).map(|definition| vec![definition]),
+            }.map_err(|e| issue_at(None, e))?
+        },
+        _ => {
+            let value: serde_yaml::Value = serde_yaml::from_str(&content)
+                .map_err(|e| issue_at(e.location().map(|location| location.line()), e))?;
+            match value.is_sequence() {
+                true => serde_yaml::from_value(value),
+                false => serde_yaml::from_value(value).map(|definition| vec![definition]),
+            }.map_err(|e| issue_at(None, e))?
+        },
+    };
+
+    Ok(definitions.into_iter()
+        .map(|definition| ParsedDefinition {
+            issues: definition.validate(dialect),
+            item_key_line: None,
+            definition,
+        })
+        .collect())
+}
+
+fn issue_at(line: Option<usize>, message: impl ToString) -> SqlDefinitionIssue {
+    SqlDefinitionIssue {
+        line,
+        ..SqlDefinitionIssue::file(String::new(), message)
+    }
+}
+
+#[async_trait]
+impl QueryRepository for FileQueryRepository
+{
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        self.queries().get_query(item_key).await
+    }
+
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
+    {
+        self.queries().get_params(item_key).await
+    }
+
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>
+    {
+        self.queries().list().await
+    }
+}
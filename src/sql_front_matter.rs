@@ -0,0 +1,201 @@
+use serde_yaml::Value;
+use crate::sql_query_definition::{SqlDefinitionIssue, SqlQueryDefinition};
+use crate::sql_safety::SqlDialect;
+
+/// Fichier `.sql` dont l'en-tête (front-matter YAML placé dans des commentaires `--`) décrit la requête.
+/// L'en-tête accepte tous les champs de `SqlQueryDefinition` sauf `sql_code`, qui est le corps du fichier.
+/// # Exemple
+/// ```sql
+/// -- ---
+/// -- item_key: select.atelier
+/// -- name: Atelier
+/// -- description: Recherche d'un atelier par identifiant
+/// -- allowed_roles: [rh]
+/// -- params:
+/// --   - param_name: id
+/// --     param_type: BIGINT
+/// --     param_order: 1
+/// --     is_required: true
+/// -- ---
+/// SELECT id, item_name FROM atelier WHERE id = $1
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqlFrontMatterFile
+{
+    definition: SqlQueryDefinition,
+    /// Lignes de l'en-tête (numéro de ligne dans le fichier, texte sans le préfixe `--`)
+    header: Vec<(usize, String)>,
+    /// Ligne du délimiteur ouvrant l'en-tête
+    header_line: usize,
+    /// Première ligne du code SQL
+    sql_line: usize,
+}
+
+impl SqlFrontMatterFile {
+    /// Délimiteur ouvrant et fermant l'en-tête (`-- ---`)
+    pub const DELIMITER: &'static str = "---";
+
+    /// Lit le contenu d'un fichier `.sql`.
+    /// # Returns
+    /// * `Err(SqlDefinitionIssue)`: Si l'en-tête est absent, mal formé ou invalide (`path` vide, `line` renseignée)
+    pub fn parse(content: &str) -> Result<Self, SqlDefinitionIssue> {
+        let mut lines = content.lines().enumerate().map(|(index, line)| (index + 1, line));
+
+        let (header_line, first) = lines.by_ref()
+            .find(|(_, line)| !line.trim().is_empty())
+            .ok_or_else(|| issue(Some(1), "empty file (expected a '-- ---' front-matter header)"))?;
+        if comment(first).map(str::trim) != Some(Self::DELIMITER) {
+            return Err(issue(Some(header_line), "missing front-matter header (expected '-- ---' on the first line)"));
+        }
+
+        let mut header = Vec::new();
+        let mut closed = false;
+        for (number, line) in lines.by_ref() {
+            match comment(line) {
+                Some(text) if text.trim() == Self::DELIMITER => {
+                    closed = true;
+                    break;
+                },
+                Some(text) => header.push((number, text.strip_prefix(' ').unwrap_or(text).to_string())),
+                None if line.trim().is_empty() => header.push((number, String::new())),
+                None => return Err(issue(Some(number), "front-matter lines must be SQL comments starting with '--'")),
+            }
+        }
+        if !closed {
+            return Err(issue(Some(header_line), "unterminated front-matter header (expected a closing '-- ---')"));
+        }
+
+        let body: Vec<(usize, &str)> = lines.collect();
+        let sql_line = body.iter()
+            .find(|(_, line)| !line.trim().is_empty())
+            .or(body.first())
+            .map(|(number, _)| *number)
+            .unwrap_or(header_line + header.len() + 2);
+        let sql_code = body.iter().map(|(_, line)| *line).collect::<Vec<_>>().join("\n").trim().to_string();
+
+        let mut file = Self {
+            definition: SqlQueryDefinition::default(),
+            header,
+            header_line,
+            sql_line,
+        };
+        file.definition = file.parse_header(sql_code)?;
+        Ok(file)
+    }
+
+    fn parse_header(&self, sql_code: String) -> Result<SqlQueryDefinition, SqlDefinitionIssue> {
+        let yaml = self.header.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join("\n");
+
+        let mut value: Value = serde_yaml::from_str(&yaml).map_err(|e| {
+            let line = e.location().and_then(|location| self.header.get(location.line().saturating_sub(1)).map(|(number, _)| *number));
+            issue(line.or(Some(self.header_line)), e)
+        })?;
+        let mapping = value.as_mapping_mut()
+            .ok_or_else(|| issue(Some(self.header_line), "front-matter header must be a YAML mapping"))?;
+        if mapping.contains_key("sql_code") {
+            let line = key_line(&self.header, "sql_code", Some(0)).unwrap_or(self.header_line);
+            return Err(issue(Some(line), "sql_code must not be declared in the header: it is the body of the file"));
+        }
+        mapping.insert(Value::from("sql_code"), Value::from(sql_code));
+
+        serde_yaml::from_value(value).map_err(|e| {
+            let message = e.to_string();
+            // Les erreurs de structure citent le champ en cause entre accents graves (ex : unknown field `sql`)
+            let field = message.split('`').nth(1).unwrap_or_default();
+            let line = key_line(&self.header, field, None).unwrap_or(self.header_line);
+            issue(Some(line), message)
+        })
+    }
+
+    /// Retourne la définition lue.
+    pub fn definition(&self) -> &SqlQueryDefinition {
+        &self.definition
+    }
+
+    /// Retourne la définition lue.
+    pub fn into_definition(self) -> SqlQueryDefinition {
+        self.definition
+    }
+
+    /// Vérifie la définition (voir `SqlQueryDefinition::validate`), chaque problème portant la ligne du fichier concernée.
+    pub fn validate(&self, dialect: SqlDialect) -> Vec<SqlDefinitionIssue> {
+        self.definition.validate(dialect)
+            .into_iter()
+            .map(|issue| SqlDefinitionIssue {
+                line: Some(self.line_of(issue.field.as_deref())),
+                ..issue
+            })
+            .collect()
+    }
+
+    /// Ligne du fichier correspondant à un champ de la définition (ex : `name`, `params[1].param_type`, `sql_code`),
+    /// à défaut la ligne ouvrant l'en-tête.
+    pub fn line_of(&self, field: Option<&str>) -> usize {
+        let field = match field {
+            Some("sql_code") => return self.sql_line,
+            Some(field) => field,
+            None => return self.header_line,
+        };
+
+        let line = match field.strip_prefix("params[").and_then(|rest| rest.split_once("].")) {
+            Some((index, key)) => index.parse::<usize>().ok().and_then(|index| self.param_line(index, key)),
+            None => key_line(&self.header, field, Some(0)),
+        };
+        line.unwrap_or(self.header_line)
+    }
+
+    /// Ligne d'une clé du paramètre d'indice `index` de la liste `params`, à défaut la ligne de début du paramètre.
+    fn param_line(&self, index: usize, key: &str) -> Option<usize> {
+        let start = self.header.iter().position(|(_, text)| indentation(text) == 0 && entry_key(text) == Some("params"))?;
+        let section: Vec<(usize, String)> = self.header[start + 1..].iter()
+            .take_while(|(_, text)| text.trim().is_empty() || indentation(text) > 0 || text.starts_with('-'))
+            .cloned()
+            .collect();
+
+        let item_indent = section.iter().find(|(_, text)| text.trim_start().starts_with('-')).map(|(_, text)| indentation(text))?;
+        let items: Vec<usize> = section.iter()
+            .enumerate()
+            .filter(|(_, (_, text))| indentation(text) == item_indent && text.trim_start().starts_with('-'))
+            .map(|(position, _)| position)
+            .collect();
+
+        let first = *items.get(index)?;
+        let last = items.get(index + 1).copied().unwrap_or(section.len());
+        key_line(&section[first..last], key, None)
+            .or(Some(section[first].0))
+    }
+}
+
+/// Ligne d'une clé parmi les lignes données, de préférence à l'indentation `indent`.
+fn key_line(lines: &[(usize, String)], key: &str, indent: Option<usize>) -> Option<usize> {
+    if key.is_empty() {
+        return None;
+    }
+    lines.iter()
+        .find(|(_, text)| indent.is_none_or(|indent| indentation(text) == indent) && entry_key(text) == Some(key))
+        .or_else(|| lines.iter().find(|(_, text)| entry_key(text) == Some(key)))
+        .map(|(number, _)| *number)
+}
+
+/// Texte d'un commentaire SQL `--`, `None` si la ligne n'est pas un commentaire.
+fn comment(line: &str) -> Option<&str> {
+    line.trim_start().strip_prefix("--")
+}
+
+fn indentation(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+/// Clé YAML portée par une ligne (`key: ...` ou `- key: ...`).
+fn entry_key(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    let text = text.strip_prefix('-').map(str::trim_start).unwrap_or(text);
+    text.split_once(':').map(|(key, _)| key.trim())
+}
+
+fn issue(line: Option<usize>, message: impl ToString) -> SqlDefinitionIssue {
+    SqlDefinitionIssue {
+        line,
+        ..SqlDefinitionIssue::file(String::new(), message)
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Mode d'import d'un `SqlRegistryDocument` (voir `SqlQueryManager::import`).
+/// Chaque mode peut être simulé sans écriture (voir `SqlImportOptions::dry_run`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlImportMode
+{
+    /// Crée les requêtes absentes, laisse les requêtes existantes intactes (signalées dans `skipped` si elles diffèrent)
+    InsertOnly,
+
+    /// Crée les requêtes absentes et met à jour les requêtes existantes qui diffèrent
+    Upsert,
+
+    /// Comme `Upsert`, puis supprime les requêtes sélectionnées par le filtre du document qui n'y figurent pas
+    Sync,
+}
+
+impl SqlImportMode {
+    /// Identifiant du mode (`insert_only`, `upsert`, `sync`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SqlImportMode::InsertOnly => "insert_only",
+            SqlImportMode::Upsert => "upsert",
+            SqlImportMode::Sync => "sync",
+        }
+    }
+}
+
+impl fmt::Display for SqlImportMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Options d'un import : mode, et simulation éventuelle.
+/// Un `SqlImportMode` se convertit en options sans simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SqlImportOptions
+{
+    pub mode: SqlImportMode,
+    /// Si vrai, rien n'est écrit : le rapport décrit exactement ce que ferait `mode`
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl SqlImportOptions {
+    /// Import dans le mode donné.
+    pub fn new(mode: SqlImportMode) -> Self {
+        Self { mode, dry_run: false }
+    }
+
+    /// Simulation d'un import dans le mode donné : rien n'est écrit.
+    pub fn dry_run(mode: SqlImportMode) -> Self {
+        Self { mode, dry_run: true }
+    }
+}
+
+impl From<SqlImportMode> for SqlImportOptions {
+    fn from(mode: SqlImportMode) -> Self {
+        Self::new(mode)
+    }
+}
+
+/// Résultat d'un import, par item_key (listes triées).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SqlImportReport
+{
+    pub mode: SqlImportMode,
+    /// Vrai si l'import a été simulé (rien n'a été écrit)
+    pub dry_run: bool,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Requêtes existantes différentes du document, non modifiées en mode `InsertOnly`
+    pub skipped: Vec<String>,
+}
+
+impl SqlImportReport {
+    /// Crée un rapport vide.
+    pub fn new(options: SqlImportOptions) -> Self {
+        Self {
+            mode: options.mode,
+            dry_run: options.dry_run,
+            created: Vec::new(),
+            updated: Vec::new(),
+            deleted: Vec::new(),
+            unchanged: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Indique si l'import modifie (ou, simulé, modifierait) le registre.
+    pub fn has_changes(&self) -> bool {
+        !(self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty())
+    }
+}
+
+impl fmt::Display for SqlImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}: {} created, {} updated, {} deleted, {} unchanged, {} skipped",
+            self.mode,
+            if self.dry_run { " (dry run)" } else { "" },
+            self.created.len(),
+            self.updated.len(),
+            self.deleted.len(),
+            self.unchanged.len(),
+            self.skipped.len()
+        )
+    }
+}
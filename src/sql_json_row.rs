@@ -0,0 +1,140 @@
+use serde_json::{Map, Number, Value};
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::PgRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::types::{chrono, Decimal, Uuid};
+use sqlx::{Column, FromRow, Row, TypeInfo, ValueRef};
+
+/// Ligne de résultat sans schéma, convertie en objet JSON (`nom de colonne -> valeur`).
+/// Utilisée lorsque l'appelant ne dispose pas d'une structure `FromRow` pour la requête.
+/// # Conversions
+/// - booléens, entiers et flottants : valeurs JSON natives
+/// - `NUMERIC` : nombre JSON si représentable, sinon chaîne
+/// - dates et heures : chaînes ISO 8601
+/// - `JSON` / `JSONB` : valeur JSON telle quelle
+/// - `NULL` : `null`
+/// - autres types : chaîne si le décodage texte est possible
+///
+/// Avec SQLite, la conversion suit la classe de stockage de chaque valeur (`INTEGER`, `REAL`, `TEXT`) ;
+/// les colonnes déclarées `BOOLEAN` deviennent des booléens, les dates et le JSON restent des chaînes.
+///
+/// Avec MySQL, les conversions sont les mêmes qu'avec PostgreSQL (`TINYINT(1)` étant un booléen) ;
+/// `DATETIME` devient `AAAA-MM-JJ HH:MM:SS` et `TIMESTAMP` une date RFC 3339 en UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlJsonRow(pub Value);
+
+impl SqlJsonRow {
+    /// Retourne la valeur JSON de la ligne.
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for SqlJsonRow {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let index = column.ordinal();
+            let value = match column.type_info().name() {
+                "BOOL" => row.try_get::<Option<bool>, _>(index)?.map(Value::Bool),
+                "INT2" => row.try_get::<Option<i16>, _>(index)?.map(Value::from),
+                "INT4" => row.try_get::<Option<i32>, _>(index)?.map(Value::from),
+                "INT8" => row.try_get::<Option<i64>, _>(index)?.map(Value::from),
+                "FLOAT4" => row.try_get::<Option<f32>, _>(index)?.map(|v| float_to_json(v as f64)),
+                "FLOAT8" => row.try_get::<Option<f64>, _>(index)?.map(float_to_json),
+                "NUMERIC" => row.try_get::<Option<Decimal>, _>(index)?.map(|v| {
+                    v.to_string().parse::<Number>()
+                        .map(Value::Number)
+                        .unwrap_or_else(|_| Value::String(v.to_string()))
+                }),
+                "DATE" => row.try_get::<Option<chrono::NaiveDate>, _>(index)?
+                    .map(|v| Value::String(v.format("%Y-%m-%d").to_string())),
+                "TIMESTAMP" => row.try_get::<Option<chrono::NaiveDateTime>, _>(index)?
+                    .map(|v| Value::String(v.format("%Y-%m-%d %H:%M:%S").to_string())),
+                "TIMESTAMPTZ" => row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(index)?
+                    .map(|v| Value::String(v.to_rfc3339())),
+                "TIME" => row.try_get::<Option<chrono::NaiveTime>, _>(index)?
+                    .map(|v| Value::String(v.to_string())),
+                "UUID" => row.try_get::<Option<Uuid>, _>(index)?
+                    .map(|v| Value::String(v.to_string())),
+                "JSON" | "JSONB" => row.try_get::<Option<Value>, _>(index)?,
+                _ => row.try_get_unchecked::<Option<String>, _>(index)?.map(Value::String),
+            };
+
+            object.insert(column.name().to_string(), value.unwrap_or(Value::Null));
+        }
+
+        Ok(SqlJsonRow(Value::Object(object)))
+    }
+}
+
+impl<'r> FromRow<'r, SqliteRow> for SqlJsonRow {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let index = column.ordinal();
+            let raw = row.try_get_raw(index)?;
+            let value = match raw.is_null() {
+                true => None,
+                false => {
+                    let storage = raw.type_info().name().to_string();
+                    match (column.type_info().name(), storage.as_str()) {
+                        ("BOOLEAN", _) => Some(Value::Bool(row.try_get_unchecked::<bool, _>(index)?)),
+                        (_, "INTEGER") => Some(Value::from(row.try_get_unchecked::<i64, _>(index)?)),
+                        (_, "REAL") => Some(float_to_json(row.try_get_unchecked::<f64, _>(index)?)),
+                        _ => Some(Value::String(row.try_get_unchecked::<String, _>(index)?)),
+                    }
+                },
+            };
+
+            object.insert(column.name().to_string(), value.unwrap_or(Value::Null));
+        }
+
+        Ok(SqlJsonRow(Value::Object(object)))
+    }
+}
+
+impl<'r> FromRow<'r, MySqlRow> for SqlJsonRow {
+    fn from_row(row: &'r MySqlRow) -> Result<Self, sqlx::Error> {
+        let mut object = Map::new();
+
+        for column in row.columns() {
+            let index = column.ordinal();
+            let value = match column.type_info().name() {
+                "BOOLEAN" => row.try_get::<Option<bool>, _>(index)?.map(Value::Bool),
+                "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" =>
+                    row.try_get::<Option<i64>, _>(index)?.map(Value::from),
+                "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED" | "YEAR" =>
+                    row.try_get::<Option<u64>, _>(index)?.map(Value::from),
+                "FLOAT" => row.try_get::<Option<f32>, _>(index)?.map(|v| float_to_json(v as f64)),
+                "DOUBLE" => row.try_get::<Option<f64>, _>(index)?.map(float_to_json),
+                "DECIMAL" => row.try_get::<Option<Decimal>, _>(index)?.map(|v| {
+                    v.to_string().parse::<Number>()
+                        .map(Value::Number)
+                        .unwrap_or_else(|_| Value::String(v.to_string()))
+                }),
+                "DATE" => row.try_get::<Option<chrono::NaiveDate>, _>(index)?
+                    .map(|v| Value::String(v.format("%Y-%m-%d").to_string())),
+                "DATETIME" => row.try_get::<Option<chrono::NaiveDateTime>, _>(index)?
+                    .map(|v| Value::String(v.format("%Y-%m-%d %H:%M:%S").to_string())),
+                "TIMESTAMP" => row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(index)?
+                    .map(|v| Value::String(v.to_rfc3339())),
+                "TIME" => row.try_get::<Option<chrono::NaiveTime>, _>(index)?
+                    .map(|v| Value::String(v.to_string())),
+                "JSON" => row.try_get::<Option<Value>, _>(index)?,
+                _ => row.try_get_unchecked::<Option<String>, _>(index)?.map(Value::String),
+            };
+
+            object.insert(column.name().to_string(), value.unwrap_or(Value::Null));
+        }
+
+        Ok(SqlJsonRow(Value::Object(object)))
+    }
+}
+
+/// Les flottants non finis (NaN, infini) n'ont pas de représentation JSON et deviennent `null`.
+fn float_to_json(value: f64) -> Value {
+    Number::from_f64(value).map(Value::Number).unwrap_or(Value::Null)
+}
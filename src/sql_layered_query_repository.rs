@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_repository::QueryRepository;
+
+/// Combinaison de plusieurs dépôts de définitions, par ordre de priorité :
+/// une requête est lue (avec ses paramètres) dans la première couche qui la définit.
+/// # Exemple
+/// Des fichiers surchargeant les requêtes stockées en base :
+/// `LayeredQueryRepository::new().with_layer(files).with_layer(pg)`
+#[derive(Debug, Clone, Default)]
+pub struct LayeredQueryRepository
+{
+    layers: Vec<Arc<dyn QueryRepository>>,
+}
+
+impl LayeredQueryRepository {
+    /// Crée une combinaison sans couche.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute une couche, moins prioritaire que les couches déjà ajoutées.
+    pub fn with_layer(mut self, layer: impl QueryRepository + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Ajoute une couche partagée, moins prioritaire que les couches déjà ajoutées.
+    pub fn with_shared_layer(mut self, layer: Arc<dyn QueryRepository>) -> Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+#[async_trait]
+impl QueryRepository for LayeredQueryRepository
+{
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        for layer in &self.layers {
+            if let Some(query) = layer.get_query(item_key).await? {
+                return Ok(Some(query));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
+    {
+        for layer in &self.layers {
+            if let Some(params) = layer.get_params(item_key).await? {
+                return Ok(Some(params));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>
+    {
+        let mut queries = BTreeMap::new();
+        for layer in &self.layers {
+            for query in layer.list().await? {
+                queries.entry(query.item_key.clone()).or_insert(query);
+            }
+        }
+        Ok(queries.into_values().collect())
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_definition::SqlQueryDefinition;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_registry_document::SqlRegistryDocument;
+
+/// Définitions de requêtes conservées en mémoire, par item_key.
+/// Utile pour les tests (aucune base de données nécessaire) ou pour des requêtes embarquées dans l'application.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryQueryRepository
+{
+    queries: BTreeMap<String, (SqlQuery, Vec<SqlQueryParam>)>,
+}
+
+impl MemoryQueryRepository {
+    /// Crée un dépôt vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crée un dépôt contenant les définitions données.
+    pub fn from_definitions(definitions: impl IntoIterator<Item = SqlQueryDefinition>) -> Self {
+        let mut repository = Self::new();
+        for definition in definitions {
+            repository.insert_definition(&definition);
+        }
+        repository
+    }
+
+    /// Ajoute (ou remplace) une requête et ses paramètres.
+    /// Le `item_key` des paramètres est aligné sur celui de la requête.
+    pub fn insert(&mut self, query: SqlQuery, params: Vec<SqlQueryParam>) {
+        let mut params = params;
+        for param in params.iter_mut() {
+            param.item_key = query.item_key.clone();
+        }
+        params.sort_by_key(|param| param.param_order);
+        self.queries.insert(query.item_key.clone(), (query, params));
+    }
+
+    /// Ajoute (ou remplace) une requête à partir de sa définition.
+    pub fn insert_definition(&mut self, definition: &SqlQueryDefinition) {
+        let (query, params) = definition.to_parts();
+        self.insert(query, params);
+    }
+
+    /// Retire une requête, renvoyant `true` si elle existait.
+    pub fn remove(&mut self, item_key: &str) -> bool {
+        self.queries.remove(item_key).is_some()
+    }
+
+    /// Indique si une requête existe pour cette item_key.
+    pub fn contains(&self, item_key: &str) -> bool {
+        self.queries.contains_key(item_key)
+    }
+
+    /// Nombre de requêtes.
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Indique si le dépôt est vide.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
+/// Dépôt contenant les requêtes d'un document exporté (par exemple pour le comparer à un environnement).
+impl From<SqlRegistryDocument> for MemoryQueryRepository {
+    fn from(document: SqlRegistryDocument) -> Self {
+        Self::from_definitions(document.queries)
+    }
+}
+
+#[async_trait]
+impl QueryRepository for MemoryQueryRepository
+{
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        Ok(self.queries.get(item_key).map(|(query, _)| query.clone()))
+    }
+
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
+    {
+        Ok(self.queries.get(item_key).map(|(_, params)| params.clone()))
+    }
+
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>
+    {
+        Ok(self.queries.values().map(|(query, _)| query.clone()).collect())
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use serde::{Deserialize, Serialize};
+use crate::sql_query_param_type::SqlQueryParamType;
+
+/// Contraintes déclaratives sur la valeur d'un paramètre, stockées en JSON
+/// dans la colonne `constraints` de la table des paramètres.
+/// # Exemple
+/// ```json
+/// { "min": 1, "max": 100, "min_length": 2, "max_length": 50, "pattern": "[A-Z]{2}[0-9]+", "allowed_values": ["A", "B"] }
+/// ```
+/// # Champs
+/// - `min` / `max`: Bornes inclusives pour les paramètres numériques (`BIGINT`, `INTEGER`, `DOUBLE PRECISION`)
+/// - `min_length` / `max_length`: Longueur de la valeur en caractères
+/// - `pattern`: Expression régulière que la valeur entière doit respecter (voir `SqlPattern`)
+/// - `allowed_values`: Liste exhaustive des valeurs acceptées
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlParamConstraints
+{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<SqlPattern>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<Vec<String>>,
+}
+
+/// Expression régulière d'une contrainte `pattern`, stockée en texte et compilée une seule fois,
+/// à sa première vérification (`SqlParamConstraints::validate`) ou utilisation.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct SqlPattern
+{
+    source: String,
+    compiled: OnceLock<Result<Regex, String>>,
+}
+
+impl SqlPattern {
+    /// Retourne l'expression régulière telle que déclarée.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Retourne l'expression régulière compilée, ancrée sur la valeur entière.
+    /// # Returns
+    /// * `Err(SqlConstraintViolation::InvalidPattern)`: Si l'expression régulière est invalide
+    pub fn regex(&self) -> Result<&Regex, SqlConstraintViolation> {
+        self.compiled
+            .get_or_init(|| Regex::new(&format!("^(?:{})$", self.source)).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|message| SqlConstraintViolation::InvalidPattern {
+                pattern: self.source.clone(),
+                message: message.clone(),
+            })
+    }
+}
+
+impl From<String> for SqlPattern {
+    fn from(source: String) -> Self {
+        Self { source, compiled: OnceLock::new() }
+    }
+}
+
+impl From<&str> for SqlPattern {
+    fn from(source: &str) -> Self {
+        source.to_string().into()
+    }
+}
+
+impl From<SqlPattern> for String {
+    fn from(pattern: SqlPattern) -> Self {
+        pattern.source
+    }
+}
+
+impl std::ops::Deref for SqlPattern {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for SqlPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl fmt::Debug for SqlPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.source, f)
+    }
+}
+
+impl fmt::Display for SqlPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Contrainte non respectée par une valeur.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "constraint", rename_all = "snake_case")]
+pub enum SqlConstraintViolation
+{
+    Min { min: f64 },
+    Max { max: f64 },
+    MinLength { min_length: usize },
+    MaxLength { max_length: usize },
+    Pattern { pattern: String },
+    AllowedValues { allowed_values: Vec<String> },
+
+    /// L'expression régulière déclarée est elle-même invalide
+    InvalidPattern { pattern: String, message: String },
+}
+
+impl fmt::Display for SqlConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlConstraintViolation::Min { min } => write!(f, "must be greater than or equal to {}", min),
+            SqlConstraintViolation::Max { max } => write!(f, "must be less than or equal to {}", max),
+            SqlConstraintViolation::MinLength { min_length } => write!(f, "must be at least {} characters long", min_length),
+            SqlConstraintViolation::MaxLength { max_length } => write!(f, "must be at most {} characters long", max_length),
+            SqlConstraintViolation::Pattern { pattern } => write!(f, "must match the pattern '{}'", pattern),
+            SqlConstraintViolation::AllowedValues { allowed_values } => write!(f, "must be one of: {}", allowed_values.join(", ")),
+            SqlConstraintViolation::InvalidPattern { pattern, message } => write!(f, "has an invalid pattern '{}': {}", pattern, message),
+        }
+    }
+}
+
+impl SqlParamConstraints {
+    /// Vérifie les contraintes elles-mêmes : l'expression régulière déclarée doit être valide.
+    /// # Returns
+    /// * `Err(SqlConstraintViolation::InvalidPattern)`: Si `pattern` ne peut pas être compilée
+    pub fn validate(&self) -> Result<(), SqlConstraintViolation> {
+        match &self.pattern {
+            Some(pattern) => pattern.regex().map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Vérifie une valeur (dont le type a déjà été validé) et renvoie toutes les contraintes non respectées.
+    ///
+    /// # Arguments
+    /// * `param_type` - Le type déclaré du paramètre
+    /// * `value` - La valeur à vérifier
+    pub fn check(&self, param_type: &SqlQueryParamType, value: &str) -> Vec<SqlConstraintViolation>
+    {
+        let mut violations = Vec::new();
+
+        // Bornes numériques
+        if matches!(param_type, SqlQueryParamType::I32 | SqlQueryParamType::F64) {
+            if let Ok(number) = value.parse::<f64>() {
+                if let Some(min) = self.min {
+                    if number < min {
+                        violations.push(SqlConstraintViolation::Min { min });
+                    }
+                }
+                if let Some(max) = self.max {
+                    if number > max {
+                        violations.push(SqlConstraintViolation::Max { max });
+                    }
+                }
+            }
+        }
+
+        // Longueur en caractères
+        let length = value.chars().count();
+        if let Some(min_length) = self.min_length {
+            if length < min_length {
+                violations.push(SqlConstraintViolation::MinLength { min_length });
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                violations.push(SqlConstraintViolation::MaxLength { max_length });
+            }
+        }
+
+        // Expression régulière, appliquée à la valeur entière
+        if let Some(pattern) = &self.pattern {
+            match pattern.regex() {
+                Ok(re) => {
+                    if !re.is_match(value) {
+                        violations.push(SqlConstraintViolation::Pattern { pattern: pattern.to_string() });
+                    }
+                },
+                Err(violation) => violations.push(violation),
+            }
+        }
+
+        // Liste des valeurs autorisées
+        if let Some(allowed_values) = &self.allowed_values {
+            if !allowed_values.iter().any(|allowed| allowed == value) {
+                violations.push(SqlConstraintViolation::AllowedValues { allowed_values: allowed_values.clone() });
+            }
+        }
+
+        violations
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+use crate::sql_param_constraints::SqlConstraintViolation;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_rule::SqlQueryRule;
+
+/// Nature d'un problème détecté sur un paramètre lors de la validation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SqlParamIssue
+{
+    /// Paramètre requis absent
+    Missing,
+
+    /// Paramètre fourni mais non déclaré pour la requête
+    Unexpected,
+
+    /// Valeur ne correspondant pas au type déclaré
+    InvalidType { expected_type: String, value: String },
+
+    /// Type déclaré non pris en charge par le moteur
+    UnknownType { param_type: String },
+
+    /// Valeur ne respectant pas une contrainte déclarée
+    ConstraintViolation { value: String, violation: SqlConstraintViolation },
+
+    /// Règle portant sur plusieurs paramètres non respectée
+    RuleViolation { rule: SqlQueryRule },
+}
+
+impl SqlParamIssue {
+    /// Code stable identifiant la nature du problème (identique à `SqlQueryEngineError::code`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            SqlParamIssue::Missing => "missing_param",
+            SqlParamIssue::Unexpected => "unexpected_param",
+            SqlParamIssue::InvalidType { .. } => "invalid_param",
+            SqlParamIssue::UnknownType { .. } => "unknown_param_type",
+            SqlParamIssue::ConstraintViolation { .. } => "constraint_violation",
+            SqlParamIssue::RuleViolation { .. } => "rule_violation",
+        }
+    }
+}
+
+impl fmt::Display for SqlParamIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlParamIssue::Missing => write!(f, "This parameter is required"),
+            SqlParamIssue::Unexpected => write!(f, "This parameter is not expected by the query"),
+            SqlParamIssue::InvalidType { expected_type, value } =>
+                write!(f, "Expected a {} value, got '{}'", expected_type, value),
+            SqlParamIssue::UnknownType { param_type } =>
+                write!(f, "Unknown parameter type '{}'", param_type),
+            SqlParamIssue::ConstraintViolation { value, violation } =>
+                write!(f, "Value '{}' {}", value, violation),
+            SqlParamIssue::RuleViolation { rule } => write!(f, "{}", rule),
+        }
+    }
+}
+
+/// Problème détecté sur un paramètre nommé.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlParamError
+{
+    pub param_name: String,
+    #[serde(flatten)]
+    pub issue: SqlParamIssue,
+}
+
+impl SqlParamError {
+    /// Convertit le problème en `SqlQueryEngineError` pour la requête donnée.
+    pub fn into_engine_error(self, item_key: &str) -> SqlQueryEngineError {
+        let item_key = item_key.to_string();
+        let param_name = self.param_name;
+
+        match self.issue {
+            SqlParamIssue::Missing => SqlQueryEngineError::ErrorMissingParam { item_key, param_name },
+            SqlParamIssue::Unexpected => SqlQueryEngineError::ErrorUnexpectedParam { item_key, param_name },
+            SqlParamIssue::InvalidType { expected_type, value } =>
+                SqlQueryEngineError::ErrorInvalidParam { item_key, param_name, expected_type, value },
+            SqlParamIssue::UnknownType { param_type } =>
+                SqlQueryEngineError::ErrorUnknownParamType { item_key, param_name, param_type },
+            SqlParamIssue::ConstraintViolation { value, violation } =>
+                SqlQueryEngineError::ErrorConstraintViolation { item_key, param_name, value, violation },
+            SqlParamIssue::RuleViolation { rule } =>
+                SqlQueryEngineError::ErrorRuleViolation { item_key, param_name, rule },
+        }
+    }
+}
+
+/// Rapport de validation listant tous les problèmes détectés sur les paramètres d'une requête,
+/// dans l'ordre où ils ont été vérifiés.
+/// `field_errors()` regroupe les messages par paramètre, pour les afficher sous les champs d'un formulaire.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SqlParamValidationReport
+{
+    pub item_key: String,
+    pub errors: Vec<SqlParamError>,
+}
+
+impl SqlParamValidationReport {
+    /// Crée un rapport vide pour la requête donnée.
+    pub fn new(item_key: impl Into<String>) -> Self {
+        Self {
+            item_key: item_key.into(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Ajoute un problème pour un paramètre.
+    pub fn push(&mut self, param_name: impl Into<String>, issue: SqlParamIssue) {
+        self.errors.push(SqlParamError {
+            param_name: param_name.into(),
+            issue,
+        });
+    }
+
+    /// Indique si aucun problème n'a été détecté.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Problèmes détectés pour un paramètre.
+    pub fn issues_for(&self, param_name: &str) -> Vec<&SqlParamIssue> {
+        self.errors.iter()
+            .filter(|e| e.param_name == param_name)
+            .map(|e| &e.issue)
+            .collect()
+    }
+
+    /// Problèmes regroupés par paramètre.
+    pub fn by_param(&self) -> BTreeMap<&str, Vec<&SqlParamIssue>> {
+        let mut grouped: BTreeMap<&str, Vec<&SqlParamIssue>> = BTreeMap::new();
+        for error in &self.errors {
+            grouped.entry(error.param_name.as_str()).or_default().push(&error.issue);
+        }
+        grouped
+    }
+
+    /// Messages d'erreur regroupés par paramètre (`nom du champ -> messages`).
+    pub fn field_errors(&self) -> BTreeMap<String, Vec<String>> {
+        let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for error in &self.errors {
+            grouped.entry(error.param_name.clone()).or_default().push(error.issue.to_string());
+        }
+        grouped
+    }
+
+    /// Renvoie le premier problème détecté sous forme de `SqlQueryEngineError`.
+    pub fn into_first_error(self) -> Result<(), SqlQueryEngineError> {
+        match self.errors.into_iter().next() {
+            Some(error) => Err(error.into_engine_error(&self.item_key)),
+            None => Ok(()),
+        }
+    }
+
+    /// Renvoie tous les problèmes détectés dans une seule `SqlQueryEngineError::ErrorValidation`.
+    pub fn into_result(self) -> Result<(), SqlQueryEngineError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(SqlQueryEngineError::ErrorValidation {
+                item_key: self.item_key.clone(),
+                report: self,
+            })
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_repository::QueryRepository;
+
+/// Définitions de requêtes stockées dans deux tables PostgreSQL (requêtes et paramètres).
+/// Les colonnes optionnelles (ex : `rules`, `constraints`) peuvent être absentes des tables.
+#[derive(Debug, Clone)]
+pub struct PgQueryRepository
+{
+    pool: PgPool,
+    table_query: String,
+    table_query_params: String,
+}
+
+impl PgQueryRepository {
+    /// Crée une nouvelle instance de `PgQueryRepository`.
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base de données
+    /// * `table_query`: Nom (qualifié) de la table contenant les requêtes
+    /// * `table_query_params`: Nom (qualifié) de la table contenant les paramètres de requête
+    pub fn new(pool: PgPool, table_query: impl Into<String>, table_query_params: impl Into<String>) -> Self {
+        Self {
+            pool,
+            table_query: table_query.into(),
+            table_query_params: table_query_params.into(),
+        }
+    }
+
+    /// Retourne le nom (qualifié) de la table contenant les requêtes.
+    pub fn table_query(&self) -> &str {
+        &self.table_query
+    }
+
+    /// Retourne le nom (qualifié) de la table contenant les paramètres de requête.
+    pub fn table_query_params(&self) -> &str {
+        &self.table_query_params
+    }
+
+    /// Indique si une requête existe pour cette item_key
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub async fn query_exists(&self, item_key: &str) -> Result<bool, SqlQueryEngineError>
+    {
+        let query = format!(
+            "SELECT EXISTS (SELECT 1 FROM {} WHERE item_key = $1)",
+            self.table_query
+        );
+
+        sqlx::query_scalar::<sqlx::Postgres, bool>(query.as_str())
+            .bind(item_key)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQuery {
+                table: self.table_query.clone(),
+                item_key: item_key.to_string(),
+                source,
+            })
+    }
+}
+
+#[async_trait]
+impl QueryRepository for PgQueryRepository
+{
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        let query  = format!(
+            "SELECT * FROM {} WHERE item_key = $1",
+            self.table_query
+        );
+
+        sqlx::query_as::<sqlx::Postgres, SqlQuery>(query.as_str())
+            .bind(item_key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQuery {
+                table: self.table_query.clone(),
+                item_key: item_key.to_string(),
+                source,
+            })
+    }
+
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
+    {
+        let query = format!(
+            r#"
+                SELECT qp.*
+                FROM {} qp
+                INNER JOIN {} q ON qp.item_key = q.item_key
+                WHERE qp.item_key = $1
+            "#,
+            self.table_query_params,
+            self.table_query
+        );
+
+        let mut params = sqlx::query_as::<sqlx::Postgres, SqlQueryParam>(query.as_str())
+            .bind(item_key)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQueryParam {
+                table: self.table_query_params.clone(),
+                item_key: item_key.to_string(),
+                source,
+            })?;
+
+        // Aucun paramètre : distinguer une requête sans paramètre d'une requête inexistante
+        if params.is_empty() && !self.query_exists(item_key).await?
+        {
+            return Ok(None);
+        }
+
+        params.sort_by_key(|p| p.param_order);
+        Ok(Some(params))
+    }
+
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>
+    {
+        let query = format!(
+            "SELECT * FROM {} ORDER BY item_key",
+            self.table_query
+        );
+
+        sqlx::query_as::<sqlx::Postgres, SqlQuery>(query.as_str())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQuery {
+                table: self.table_query.clone(),
+                item_key: String::new(),
+                source,
+            })
+    }
+}
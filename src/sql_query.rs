@@ -1,4 +1,12 @@
+use chrono::{DateTime, Utc};
 use sqlx::prelude::FromRow;
+use sqlx::types::Json;
+use crate::sql_column_policy::{SqlColumnAction, SqlColumnPolicy};
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_result::SqlQueryWarning;
+use crate::sql_query_rule::SqlQueryRule;
+use crate::sql_query_status::SqlQueryStatus;
+use crate::sql_safety::{check_sql_safety, SqlDialect, SqlSafetyViolation, SqlStatementKind};
 
 #[derive(Debug, Clone, FromRow)]
 
@@ -10,7 +18,6 @@ use sqlx::prelude::FromRow;
 /// pub item_key: String,
 /// pub sign: String,
 /// 
-
 pub struct SqlQuery {
    pub id: i32,
    pub name: String,
@@ -18,6 +25,41 @@ pub struct SqlQuery {
    pub sql_code: String,
    pub item_key: String,
    pub sign: Option<String>,
+   /// Règles portant sur plusieurs paramètres (colonne JSONB `rules`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub rules: Option<Json<Vec<SqlQueryRule>>>,
+   /// Rôles ou permissions autorisés à exécuter la requête (colonne `TEXT[]` `allowed_roles`,
+   /// optionnelle dans la table). `None` : aucune restriction, liste vide : personne.
+   #[sqlx(default)]
+   pub allowed_roles: Option<Vec<String>>,
+   /// Nature maximale autorisée pour le code SQL (`read_only`, `dml` ou `ddl`, colonne `statement_kind`
+   /// optionnelle dans la table). Ne peut que restreindre la politique du gestionnaire.
+   #[sqlx(default)]
+   pub statement_kind: Option<String>,
+   /// Si vrai, la requête est toujours exécutée dans une transaction en lecture seule
+   /// (colonne `BOOLEAN` `read_only`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub read_only: Option<bool>,
+   /// Politiques de masquage des colonnes du résultat selon les rôles de l'appelant
+   /// (colonne JSONB `column_policies`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub column_policies: Option<Json<Vec<SqlColumnPolicy>>>,
+   /// Cycle de vie de la requête (`draft`, `active`, `deprecated` ou `disabled`, colonne `status`
+   /// optionnelle dans la table). `None` : requête active.
+   #[sqlx(default)]
+   pub status: Option<String>,
+   /// Date à partir de laquelle la requête est désactivée (colonne `TIMESTAMPTZ` `sunset_at`,
+   /// optionnelle dans la table)
+   #[sqlx(default)]
+   pub sunset_at: Option<DateTime<Utc>>,
+   /// Nom de la base sur laquelle la requête est exécutée (colonne `datasource`, optionnelle dans la table,
+   /// voir `SqlQueryManagerBuilder::datasource`). `None` : pool principal du gestionnaire.
+   #[sqlx(default)]
+   pub datasource: Option<String>,
+   /// Si vrai, la requête est toujours exécutée sur le pool principal, jamais sur un réplica
+   /// (colonne `BOOLEAN` `needs_fresh_data`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub needs_fresh_data: Option<bool>,
 }
 
 impl SqlQuery {
@@ -37,8 +79,181 @@ impl SqlQuery {
             sql_code,
             item_key,
             sign,
+            rules: None,
+            allowed_roles: None,
+            statement_kind: None,
+            read_only: None,
+            column_policies: None,
+            status: None,
+            sunset_at: None,
+            datasource: None,
+            needs_fresh_data: None,
         }
     }
+
+    /// Définit les règles portant sur plusieurs paramètres de la requête.
+    pub fn with_rules(mut self, rules: Vec<SqlQueryRule>) -> Self {
+        self.rules = Some(Json(rules));
+        self
+    }
+
+    /// Restreint l'exécution de la requête aux rôles donnés.
+    pub fn with_allowed_roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_roles = Some(roles.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Indique si un appelant possédant ces rôles peut exécuter la requête.
+    /// # Arguments
+    /// * `roles`: Rôles ou permissions de l'appelant
+    pub fn is_accessible_by(&self, roles: &[String]) -> bool {
+        match &self.allowed_roles {
+            None => true,
+            Some(allowed_roles) => allowed_roles.iter().any(|allowed| roles.contains(allowed)),
+        }
+    }
+
+    /// Déclare la nature maximale autorisée pour le code SQL de la requête.
+    pub fn with_statement_kind(mut self, kind: SqlStatementKind) -> Self {
+        self.statement_kind = Some(kind.to_string());
+        self
+    }
+
+    /// Analyse le code SQL et vérifie qu'il ne contient qu'une instruction dont la nature
+    /// ne dépasse ni `max_kind` ni la nature déclarée pour la requête.
+    /// # Arguments
+    /// * `max_kind`: Nature maximale autorisée par le gestionnaire
+    /// * `dialect`: Dialecte de la base qui exécute la requête
+    /// # Returns
+    /// * `Ok(SqlStatementKind)`: La nature du code SQL
+    /// * `Err(SqlQueryEngineError::ErrorUnsafeSql)`: Si le code SQL est refusé
+    pub fn check_safety(&self, max_kind: SqlStatementKind, dialect: SqlDialect) -> Result<SqlStatementKind, SqlQueryEngineError> {
+        let unsafe_sql = |violation| SqlQueryEngineError::ErrorUnsafeSql {
+            item_key: self.item_key.clone(),
+            violation,
+        };
+
+        let max_kind = match &self.statement_kind {
+            Some(value) => value.parse::<SqlStatementKind>()
+                .map_err(|_| unsafe_sql(SqlSafetyViolation::InvalidStatementKind { value: value.clone() }))?
+                .min(max_kind),
+            None => max_kind,
+        };
+
+        check_sql_safety(&self.sql_code, max_kind, dialect).map_err(unsafe_sql)
+    }
+
+    /// Impose (ou non) l'exécution de la requête dans une transaction en lecture seule.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    /// Indique si la requête doit être exécutée dans une transaction en lecture seule.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.unwrap_or(false)
+    }
+
+    /// Définit les politiques de masquage des colonnes du résultat.
+    pub fn with_column_policies(mut self, policies: Vec<SqlColumnPolicy>) -> Self {
+        self.column_policies = Some(Json(policies));
+        self
+    }
+
+    /// Retourne les politiques de masquage des colonnes du résultat.
+    pub fn column_policies(&self) -> &[SqlColumnPolicy] {
+        self.column_policies.as_deref().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Indique si au moins une politique de masquage s'applique à un appelant possédant ces rôles.
+    /// # Arguments
+    /// * `roles`: Rôles ou permissions de l'appelant
+    pub fn is_masked_for(&self, roles: &[String]) -> bool {
+        self.column_policies().iter().any(|policy| policy.applies_to(roles))
+    }
+
+    /// Indique si au moins une politique `hash` s'applique à un appelant possédant ces rôles.
+    /// # Arguments
+    /// * `roles`: Rôles ou permissions de l'appelant
+    pub fn is_hashed_for(&self, roles: &[String]) -> bool {
+        self.column_policies().iter().any(|policy| policy.action == SqlColumnAction::Hash && policy.applies_to(roles))
+    }
+
+    /// Définit le statut de la requête dans son cycle de vie.
+    pub fn with_status(mut self, status: SqlQueryStatus) -> Self {
+        self.status = Some(status.to_string());
+        self
+    }
+
+    /// Définit la date à partir de laquelle la requête est désactivée.
+    pub fn with_sunset_at(mut self, sunset_at: DateTime<Utc>) -> Self {
+        self.sunset_at = Some(sunset_at);
+        self
+    }
+
+    /// Définit la base sur laquelle la requête est exécutée.
+    pub fn with_datasource(mut self, datasource: impl Into<String>) -> Self {
+        self.datasource = Some(datasource.into());
+        self
+    }
+
+    /// Retourne le nom de la base sur laquelle la requête est exécutée (`None` si la colonne est vide).
+    pub fn datasource(&self) -> Option<&str> {
+        self.datasource.as_deref().map(str::trim).filter(|datasource| !datasource.is_empty())
+    }
+
+    /// Impose (ou non) l'exécution de la requête sur le pool principal, pour lire des données à jour.
+    pub fn with_needs_fresh_data(mut self, needs_fresh_data: bool) -> Self {
+        self.needs_fresh_data = Some(needs_fresh_data);
+        self
+    }
+
+    /// Indique si la requête doit lire des données à jour (pool principal, jamais un réplica).
+    pub fn needs_fresh_data(&self) -> bool {
+        self.needs_fresh_data.unwrap_or(false)
+    }
+
+    /// Retourne le statut déclaré de la requête (`Active` si la colonne est vide).
+    /// Une valeur inconnue est traitée comme `Disabled` : la requête n'est pas exécutée.
+    pub fn status(&self) -> SqlQueryStatus {
+        match self.status.as_deref().map(str::trim).filter(|status| !status.is_empty()) {
+            Some(status) => status.parse().unwrap_or(SqlQueryStatus::Disabled),
+            None => SqlQueryStatus::Active,
+        }
+    }
+
+    /// Retourne le statut de la requête à une date donnée : une requête dont la date `sunset_at`
+    /// est atteinte est désactivée, quel que soit son statut déclaré.
+    /// # Arguments
+    /// * `now`: Date de référence
+    pub fn status_at(&self, now: DateTime<Utc>) -> SqlQueryStatus {
+        match self.sunset_at {
+            Some(sunset_at) if sunset_at <= now => SqlQueryStatus::Disabled,
+            _ => self.status(),
+        }
+    }
+
+    /// Retourne les avertissements à joindre au résultat d'une exécution à la date donnée.
+    /// # Arguments
+    /// * `now`: Date de référence
+    pub fn warnings_at(&self, now: DateTime<Utc>) -> Vec<SqlQueryWarning> {
+        match self.status_at(now) {
+            SqlQueryStatus::Deprecated => vec![SqlQueryWarning::Deprecated {
+                item_key: self.item_key.clone(),
+                sunset_at: self.sunset_at,
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Retourne les règles portant sur plusieurs paramètres de la requête.
+    pub fn rules(&self) -> &[SqlQueryRule] {
+        self.rules.as_deref().map(Vec::as_slice).unwrap_or_default()
+    }
     /// Retourne le code SQL de la requête.
     pub fn sql_code(&self) -> &str {
         &self.sql_code
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::sql_dynamic_query::SqlDynamicQuery;
+
+/// Cache des requêtes dynamiques partagé entre les clones d'un `SqlQueryManager`.
+/// Les entrées expirent après `ttl` et l'entrée la plus ancienne est évincée
+/// lorsque la capacité maximale est atteinte.
+/// # Champs
+/// - `ttl`: Durée de vie d'une entrée (cache désactivé si `None`)
+/// - `capacity`: Nombre maximal d'entrées conservées
+/// - `entries`: Entrées du cache indexées par item_key
+#[derive(Debug, Clone)]
+pub struct SqlQueryCache
+{
+    ttl: Option<Duration>,
+    capacity: usize,
+    entries: Arc<RwLock<HashMap<String, (Instant, SqlDynamicQuery)>>>,
+}
+
+impl SqlQueryCache {
+    /// Capacité utilisée lorsqu'aucune capacité n'est précisée.
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// Crée un nouveau cache.
+    /// # Arguments
+    /// * `ttl`: Durée de vie d'une entrée, `None` pour désactiver le cache
+    /// * `capacity`: Nombre maximal d'entrées conservées
+    pub fn new(ttl: Option<Duration>, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Crée un cache désactivé.
+    pub fn disabled() -> Self {
+        Self::new(None, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Indique si le cache est actif.
+    pub fn is_enabled(&self) -> bool {
+        self.ttl.is_some() && self.capacity > 0
+    }
+
+    /// Récupère une requête dynamique encore valide par son item_key.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub fn get(&self, item_key: &str) -> Option<SqlDynamicQuery>
+    {
+        let ttl = self.ttl?;
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+
+        match entries.get(item_key) {
+            Some((inserted_at, query)) if inserted_at.elapsed() < ttl => Some(query.clone()),
+            _ => None,
+        }
+    }
+
+    /// Ajoute (ou remplace) une requête dynamique dans le cache.
+    /// # Arguments
+    /// * `query`: Requête dynamique à conserver
+    pub fn insert(&self, query: SqlDynamicQuery)
+    {
+        let Some(ttl) = self.ttl else { return };
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+
+        // Supprimer les entrées expirées avant de vérifier la capacité
+        entries.retain(|_, (inserted_at, _)| inserted_at.elapsed() < ttl);
+
+        if entries.len() >= self.capacity && !entries.contains_key(&query.query.item_key) {
+            let oldest = entries.iter()
+                .min_by_key(|(_, (inserted_at, _))| *inserted_at)
+                .map(|(key, _)| key.clone());
+
+            if let Some(key) = oldest {
+                entries.remove(&key);
+            }
+        }
+
+        entries.insert(query.query.item_key.clone(), (Instant::now(), query));
+    }
+
+    /// Retire une requête du cache.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub fn invalidate(&self, item_key: &str)
+    {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).remove(item_key);
+    }
+
+    /// Vide entièrement le cache.
+    pub fn clear(&self)
+    {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Nombre d'entrées actuellement conservées (expirées comprises).
+    pub fn len(&self) -> usize
+    {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Indique si le cache ne contient aucune entrée.
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+}
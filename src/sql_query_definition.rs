@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use crate::sql_column_policy::SqlColumnPolicy;
+use crate::sql_default_value::resolve_default_value;
+use crate::sql_dynamic_query::SqlDynamicQuery;
+use crate::sql_execution_context::ExecutionContext;
+use crate::sql_param_constraints::SqlParamConstraints;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_param_type::SqlQueryParamType;
+use crate::sql_query_rule::SqlQueryRule;
+use crate::sql_query_status::SqlQueryStatus;
+use crate::sql_safety::{SqlDialect, SqlSafetyViolation, SqlStatementKind};
+
+/// Définition complète d'une requête (requête et paramètres) sous forme de document JSON ou YAML,
+/// indépendante du stockage.
+///
+/// Les paramètres sont listés dans l'ordre de leurs placeholders (`$1`, `$2`...),
+/// sauf si leur `param_order` est précisé.
+/// # Exemple
+/// ```yaml
+/// item_key: select.atelier
+/// name: Atelier
+/// sql_code: SELECT id, item_name FROM atelier WHERE id = $1
+/// allowed_roles: [rh]
+/// params:
+///   - param_name: id
+///     param_type: BIGINT
+///     constraints: { min: 1 }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlQueryDefinition
+{
+    pub item_key: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub sql_code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sign: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<SqlQueryRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_roles: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_policies: Vec<SqlColumnPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datasource: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs_fresh_data: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<SqlParamDefinition>,
+}
+
+/// Définition d'un paramètre dans une `SqlQueryDefinition`.
+/// Un paramètre est requis par défaut ; sans `param_order`, sa position dans la liste (à partir de 1) est utilisée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlParamDefinition
+{
+    pub param_name: String,
+    pub param_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_order: Option<i32>,
+    #[serde(default = "default_required")]
+    pub is_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<SqlParamConstraints>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl SqlQueryDefinition {
+    /// Construit la définition d'une requête stockée et de ses paramètres (triés par `param_order`).
+    /// # Arguments
+    /// * `query`: La requête
+    /// * `params`: Les paramètres de la requête
+    pub fn from_parts(query: &SqlQuery, params: &[SqlQueryParam]) -> Self {
+        let mut params: Vec<&SqlQueryParam> = params.iter().collect();
+        params.sort_by_key(|param| param.param_order);
+
+        Self {
+            item_key: query.item_key.clone(),
+            name: query.name.clone(),
+            description: query.description.clone(),
+            sql_code: query.sql_code.clone(),
+            sign: query.sign.clone(),
+            rules: query.rules().to_vec(),
+            allowed_roles: query.allowed_roles.clone(),
+            statement_kind: query.statement_kind.clone(),
+            read_only: query.read_only,
+            column_policies: query.column_policies().to_vec(),
+            status: query.status.clone(),
+            sunset_at: query.sunset_at,
+            datasource: query.datasource.clone(),
+            needs_fresh_data: query.needs_fresh_data,
+            params: params.into_iter()
+                .map(|param| SqlParamDefinition {
+                    param_name: param.param_name.clone(),
+                    param_type: param.param_type.clone(),
+                    param_order: None,
+                    is_required: param.is_required == 1,
+                    default_value: param.default_value.clone(),
+                    description: param.description.clone(),
+                    constraints: param.constraints.as_ref().map(|constraints| constraints.0.clone()),
+                    sensitive: param.sensitive,
+                })
+                .collect(),
+        }
+    }
+
+    /// Convertit la définition en requête et paramètres triés par `param_order`
+    /// (à défaut, numérotés dans l'ordre de la liste à partir de 1).
+    /// Les identifiants (`id`) sont laissés à 0 pour la requête et à `param_order` pour les paramètres.
+    pub fn to_parts(&self) -> (SqlQuery, Vec<SqlQueryParam>) {
+        let mut query = SqlQuery::new(
+            0,
+            self.name.clone(),
+            self.description.clone(),
+            self.sql_code.clone(),
+            self.item_key.clone(),
+            self.sign.clone(),
+        );
+        if !self.rules.is_empty() {
+            query = query.with_rules(self.rules.clone());
+        }
+        if !self.column_policies.is_empty() {
+            query = query.with_column_policies(self.column_policies.clone());
+        }
+        query.allowed_roles = self.allowed_roles.clone();
+        query.statement_kind = self.statement_kind.clone();
+        query.read_only = self.read_only;
+        query.status = self.status.clone();
+        query.sunset_at = self.sunset_at;
+        query.datasource = self.datasource.clone();
+        query.needs_fresh_data = self.needs_fresh_data;
+
+        let mut params: Vec<SqlQueryParam> = self.params.iter()
+            .zip(1..)
+            .map(|(param, position)| (param, param.param_order.unwrap_or(position)))
+            .map(|(param, param_order)| SqlQueryParam {
+                id: param_order,
+                param_name: param.param_name.clone(),
+                param_type: param.param_type.clone(),
+                param_order,
+                is_required: i32::from(param.is_required),
+                default_value: param.default_value.clone(),
+                description: param.description.clone(),
+                item_key: self.item_key.clone(),
+                constraints: param.constraints.clone().map(Json),
+                sensitive: param.sensitive,
+            })
+            .collect();
+        params.sort_by_key(|param| param.param_order);
+
+        (query, params)
+    }
+
+    /// Vérifie la définition sans base de données : champs obligatoires, code SQL (une seule instruction valide),
+    /// `statement_kind`, `status`, types, noms, ordres et contraintes des paramètres, valeurs par défaut littérales
+    /// et placeholders `$n` sans paramètre correspondant.
+    /// # Arguments
+    /// * `dialect`: Dialecte dans lequel le code SQL est analysé
+    /// # Returns
+    /// * Les problèmes détectés (`path` vide et `line` à `None`, renseignés par le chargeur de fichiers)
+    pub fn validate(&self, dialect: SqlDialect) -> Vec<SqlDefinitionIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |field: &str, message: String| issues.push(SqlDefinitionIssue {
+            path: String::new(),
+            line: None,
+            item_key: self.item_key.clone(),
+            field: Some(field.to_string()),
+            message,
+        });
+
+        if self.item_key.trim().is_empty() {
+            issue("item_key", "item_key must not be empty".to_string());
+        }
+        if self.name.trim().is_empty() {
+            issue("name", "name must not be empty".to_string());
+        }
+
+        if let Some(Err(reason)) = self.status.as_deref().map(str::parse::<SqlQueryStatus>) {
+            issue("status", reason);
+        }
+
+        let (query, _) = self.to_parts();
+        if let Err(SqlQueryEngineError::ErrorUnsafeSql { violation, .. }) = query.check_safety(SqlStatementKind::Ddl, dialect) {
+            let field = match violation {
+                SqlSafetyViolation::InvalidStatementKind { .. } => "statement_kind",
+                _ => "sql_code",
+            };
+            issue(field, violation.to_string());
+        }
+
+        let mut names = HashSet::new();
+        let mut orders = HashSet::new();
+        for (index, param) in self.params.iter().enumerate() {
+            let field = |name: &str| format!("params[{}].{}", index, name);
+
+            if param.param_name.trim().is_empty() {
+                issue(&field("param_name"), "param_name must not be empty".to_string());
+            } else if !names.insert(param.param_name.as_str()) {
+                issue(&field("param_name"), format!("parameter '{}' is declared twice", param.param_name));
+            }
+
+            let order = param.param_order.unwrap_or(index as i32 + 1);
+            if order < 1 {
+                issue(&field("param_order"), format!("param_order must be at least 1, got {}", order));
+            } else if !orders.insert(order) {
+                issue(&field("param_order"), format!("param_order {} is used twice", order));
+            }
+
+            if let Some(Err(violation)) = param.constraints.as_ref().map(SqlParamConstraints::validate) {
+                issue(&field("constraints"), format!("constraints {}", violation));
+            }
+
+            let param_type = match param.param_type.parse::<SqlQueryParamType>() {
+                Ok(param_type) => param_type,
+                Err(param_type) => {
+                    issue(&field("param_type"), format!("unknown parameter type '{}'", param_type));
+                    continue;
+                },
+            };
+
+            // Les valeurs issues du contexte ne sont connues qu'à l'exécution
+            if let Some(default) = param.default_value.as_deref().filter(|default| !default.trim().starts_with("${")) {
+                let now = chrono::Local::now().naive_local();
+                let valid = resolve_default_value(default, &param_type, &ExecutionContext::default(), now)
+                    .map(|value| SqlDynamicQuery::validate_param_type(&param_type, &value));
+                match valid {
+                    Ok(true) => {},
+                    Ok(false) => issue(&field("default_value"), format!("default value '{}' is not a valid {}", default, param.param_type)),
+                    Err(reason) => issue(&field("default_value"), format!("default value '{}' is invalid: {}", default, reason)),
+                }
+            }
+        }
+
+        let placeholder = Regex::new(r"\$(\d+)").expect("regex valide");
+        let max_placeholder = placeholder.captures_iter(&self.sql_code)
+            .filter_map(|captures| captures[1].parse::<usize>().ok())
+            .max()
+            .unwrap_or(0);
+        if max_placeholder > self.params.len() {
+            issue("sql_code", format!("sql_code uses ${} but only {} parameter(s) are declared", max_placeholder, self.params.len()));
+        }
+
+        issues
+    }
+}
+
+/// Problème détecté lors du chargement ou de la vérification d'une définition de requête.
+/// # Champs
+/// - `path`: Fichier de la définition (vide si elle ne provient pas d'un fichier)
+/// - `line`: Ligne du fichier concernée, si elle est connue
+/// - `item_key`: Item_key de la requête (vide si elle est inconnue)
+/// - `field`: Champ concerné (ex : `sql_code`, `params[0].param_type`)
+/// - `message`: Description du problème
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlDefinitionIssue
+{
+    pub path: String,
+    pub line: Option<usize>,
+    pub item_key: String,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl SqlDefinitionIssue {
+    /// Crée un problème portant sur un fichier entier.
+    pub fn file(path: impl Into<String>, message: impl ToString) -> Self {
+        Self {
+            path: path.into(),
+            line: None,
+            item_key: String::new(),
+            field: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SqlDefinitionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}", self.path)?;
+            if let Some(line) = self.line {
+                write!(f, ":{}", line)?;
+            }
+            write!(f, ": ")?;
+        }
+        if !self.item_key.is_empty() {
+            write!(f, "query '{}': ", self.item_key)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, "{}: ", field)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
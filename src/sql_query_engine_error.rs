@@ -1,10 +1,326 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+use std::fmt;
+use std::time::Duration;
+
+use crate::sql_param_constraints::SqlConstraintViolation;
+use crate::sql_param_validation_report::SqlParamValidationReport;
+use crate::sql_query_definition::SqlDefinitionIssue;
+use crate::sql_query_rule::SqlQueryRule;
+use crate::sql_query_status::SqlQueryStatus;
+use crate::sql_safety::SqlSafetyViolation;
+
+/// Erreurs renvoyées par le moteur de requêtes.
+/// Chaque variante porte les informations utiles au diagnostic (item_key, nom du paramètre,
+/// type attendu, valeur fournie...) et, lorsqu'elle provient de la base de données,
+/// l'erreur `sqlx::Error` d'origine accessible via `source()` (et non répétée par `Display`).
+/// `code()` fournit un identifiant stable, destiné aux réponses d'API.
+#[derive(Debug)]
 pub enum SqlQueryEngineError
 {
-   ErrorGetSqlQuery(String),
-   ErrorNoQueryFound(String),
-   ErrorGetSqlQueryParam(String),
-   ErrorGetDynamicQuery(String),
-   ErrorExecutionQuery(String),
-   ErrorCheckParams(String),
-}
\ No newline at end of file
+   /// La lecture de la table des requêtes a échoué
+   ErrorGetSqlQuery { table: String, item_key: String, source: sqlx::Error },
+
+   /// Aucune requête n'existe pour cette item_key
+   ErrorNoQueryFound { item_key: String },
+
+   /// Le chargement de définitions de requêtes depuis des fichiers a échoué
+   /// (tous les problèmes détectés, avec fichier et ligne)
+   ErrorLoadQueryDefinitions { issues: Vec<SqlDefinitionIssue> },
+
+   /// Les rôles de l'appelant ne permettent pas d'accéder à la requête
+   ErrorAccessDenied { item_key: String },
+
+   /// La requête existe mais son statut ne permet pas de l'exécuter (brouillon, désactivée ou date de fin atteinte)
+   ErrorQueryUnavailable { item_key: String, status: SqlQueryStatus },
+
+   /// La requête doit être exécutée sur une base (colonne `datasource`) inconnue du gestionnaire
+   ErrorUnknownDatasource { item_key: String, datasource: String },
+
+   /// La lecture de la table des paramètres a échoué
+   ErrorGetSqlQueryParam { table: String, item_key: String, source: sqlx::Error },
+
+   /// L'enregistrement d'une requête ou de ses paramètres a échoué
+   ErrorRegisterSqlQuery { table: String, item_key: String, source: sqlx::Error },
+
+   /// La lecture ou l'écriture de la table des révisions a échoué
+   ErrorRevision { table: String, item_key: String, source: sqlx::Error },
+
+   /// Aucune révision n'existe avec ce numéro pour cette item_key
+   ErrorRevisionNotFound { item_key: String, revision: i32 },
+
+   /// Le code SQL de la requête est refusé par l'analyse de sécurité
+   ErrorUnsafeSql { item_key: String, violation: SqlSafetyViolation },
+
+   /// Un paramètre requis n'a pas été fourni
+   ErrorMissingParam { item_key: String, param_name: String },
+
+   /// Un paramètre fourni n'est pas déclaré pour la requête
+   ErrorUnexpectedParam { item_key: String, param_name: String },
+
+   /// La valeur fournie ne correspond pas au type déclaré du paramètre
+   ErrorInvalidParam { item_key: String, param_name: String, expected_type: String, value: String },
+
+   /// Le type déclaré d'un paramètre n'est pas pris en charge
+   ErrorUnknownParamType { item_key: String, param_name: String, param_type: String },
+
+   /// La valeur fournie ne respecte pas une contrainte déclarée du paramètre
+   ErrorConstraintViolation { item_key: String, param_name: String, value: String, violation: SqlConstraintViolation },
+
+   /// Les contraintes déclarées d'un paramètre sont elles-mêmes invalides (ex : expression régulière incorrecte)
+   ErrorInvalidConstraint { item_key: String, param_name: String, violation: SqlConstraintViolation },
+
+   /// Une règle portant sur plusieurs paramètres n'est pas respectée (`param_name` : paramètre signalé)
+   ErrorRuleViolation { item_key: String, param_name: String, rule: SqlQueryRule },
+
+   /// La valeur par défaut d'un paramètre ne peut pas être résolue ou ne correspond pas au type déclaré
+   ErrorInvalidDefaultValue { item_key: String, param_name: String, default_value: String, reason: String },
+
+   /// Plusieurs paramètres sont invalides (voir `SqlDynamicQuery::validate_query_params`)
+   ErrorValidation { item_key: String, report: SqlParamValidationReport },
+
+   /// L'exécution de la requête a échoué
+   ErrorExecutionQuery { item_key: String, source: sqlx::Error },
+
+   /// La requête a tenté d'écrire dans une transaction en lecture seule (SQLSTATE `25006`)
+   ErrorReadOnlyViolation { item_key: String, source: sqlx::Error },
+
+   /// Le contexte d'exécution ne peut pas être appliqué comme variables de session
+   /// (voir `ExecutionContext::session_settings`)
+   ErrorInvalidContext { item_key: String, reason: String },
+
+   /// Des colonnes doivent être masquées pour l'appelant, ce qu'un résultat typé ne permet pas
+   /// (la requête doit être exécutée avec `run_json`)
+   ErrorMaskingUnsupported { item_key: String },
+
+   /// Des colonnes doivent être remplacées par leur empreinte pour l'appelant,
+   /// mais aucune clé n'a été fournie (voir `SqlQueryManagerBuilder::hash_key`)
+   ErrorMissingHashKey { item_key: String },
+
+   /// L'enregistrement de la trace d'audit a échoué
+   ErrorAudit { table: String, item_key: String, source: sqlx::Error },
+
+   /// La requête a renvoyé plus de lignes que la limite autorisée
+   ErrorRowLimitExceeded { item_key: String, max_rows: usize },
+
+   /// La requête a dépassé la durée maximale d'exécution
+   ErrorTimeout { item_key: String, timeout: Duration },
+}
+
+impl SqlQueryEngineError {
+    /// Code stable et lisible par une machine identifiant la nature de l'erreur.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SqlQueryEngineError::ErrorGetSqlQuery { .. } => "get_sql_query_failed",
+            SqlQueryEngineError::ErrorNoQueryFound { .. } => "query_not_found",
+            SqlQueryEngineError::ErrorLoadQueryDefinitions { .. } => "load_definitions_failed",
+            SqlQueryEngineError::ErrorAccessDenied { .. } => "access_denied",
+            SqlQueryEngineError::ErrorQueryUnavailable { .. } => "query_unavailable",
+            SqlQueryEngineError::ErrorUnknownDatasource { .. } => "unknown_datasource",
+            SqlQueryEngineError::ErrorGetSqlQueryParam { .. } => "get_sql_query_param_failed",
+            SqlQueryEngineError::ErrorRegisterSqlQuery { .. } => "register_sql_query_failed",
+            SqlQueryEngineError::ErrorRevision { .. } => "revision_failed",
+            SqlQueryEngineError::ErrorRevisionNotFound { .. } => "revision_not_found",
+            SqlQueryEngineError::ErrorUnsafeSql { .. } => "unsafe_sql",
+            SqlQueryEngineError::ErrorMissingParam { .. } => "missing_param",
+            SqlQueryEngineError::ErrorUnexpectedParam { .. } => "unexpected_param",
+            SqlQueryEngineError::ErrorInvalidParam { .. } => "invalid_param",
+            SqlQueryEngineError::ErrorUnknownParamType { .. } => "unknown_param_type",
+            SqlQueryEngineError::ErrorConstraintViolation { .. } => "constraint_violation",
+            SqlQueryEngineError::ErrorInvalidConstraint { .. } => "invalid_constraint",
+            SqlQueryEngineError::ErrorRuleViolation { .. } => "rule_violation",
+            SqlQueryEngineError::ErrorInvalidDefaultValue { .. } => "invalid_default_value",
+            SqlQueryEngineError::ErrorValidation { .. } => "validation_failed",
+            SqlQueryEngineError::ErrorExecutionQuery { .. } => "execution_failed",
+            SqlQueryEngineError::ErrorReadOnlyViolation { .. } => "read_only_violation",
+            SqlQueryEngineError::ErrorInvalidContext { .. } => "invalid_context",
+            SqlQueryEngineError::ErrorMaskingUnsupported { .. } => "masking_unsupported",
+            SqlQueryEngineError::ErrorMissingHashKey { .. } => "missing_hash_key",
+            SqlQueryEngineError::ErrorAudit { .. } => "audit_failed",
+            SqlQueryEngineError::ErrorRowLimitExceeded { .. } => "row_limit_exceeded",
+            SqlQueryEngineError::ErrorTimeout { .. } => "timeout",
+        }
+    }
+
+    /// Item_key de la requête concernée.
+    pub fn item_key(&self) -> &str {
+        match self {
+            SqlQueryEngineError::ErrorGetSqlQuery { item_key, .. }
+            | SqlQueryEngineError::ErrorNoQueryFound { item_key }
+            | SqlQueryEngineError::ErrorAccessDenied { item_key }
+            | SqlQueryEngineError::ErrorQueryUnavailable { item_key, .. }
+            | SqlQueryEngineError::ErrorUnknownDatasource { item_key, .. }
+            | SqlQueryEngineError::ErrorGetSqlQueryParam { item_key, .. }
+            | SqlQueryEngineError::ErrorRegisterSqlQuery { item_key, .. }
+            | SqlQueryEngineError::ErrorRevision { item_key, .. }
+            | SqlQueryEngineError::ErrorRevisionNotFound { item_key, .. }
+            | SqlQueryEngineError::ErrorUnsafeSql { item_key, .. }
+            | SqlQueryEngineError::ErrorMissingParam { item_key, .. }
+            | SqlQueryEngineError::ErrorUnexpectedParam { item_key, .. }
+            | SqlQueryEngineError::ErrorInvalidParam { item_key, .. }
+            | SqlQueryEngineError::ErrorUnknownParamType { item_key, .. }
+            | SqlQueryEngineError::ErrorConstraintViolation { item_key, .. }
+            | SqlQueryEngineError::ErrorInvalidConstraint { item_key, .. }
+            | SqlQueryEngineError::ErrorRuleViolation { item_key, .. }
+            | SqlQueryEngineError::ErrorInvalidDefaultValue { item_key, .. }
+            | SqlQueryEngineError::ErrorValidation { item_key, .. }
+            | SqlQueryEngineError::ErrorExecutionQuery { item_key, .. }
+            | SqlQueryEngineError::ErrorReadOnlyViolation { item_key, .. }
+            | SqlQueryEngineError::ErrorInvalidContext { item_key, .. }
+            | SqlQueryEngineError::ErrorMaskingUnsupported { item_key }
+            | SqlQueryEngineError::ErrorMissingHashKey { item_key }
+            | SqlQueryEngineError::ErrorAudit { item_key, .. }
+            | SqlQueryEngineError::ErrorRowLimitExceeded { item_key, .. }
+            | SqlQueryEngineError::ErrorTimeout { item_key, .. } => item_key,
+            SqlQueryEngineError::ErrorLoadQueryDefinitions { issues } =>
+                issues.first().map(|issue| issue.item_key.as_str()).unwrap_or_default(),
+        }
+    }
+
+    /// Nom du paramètre en cause, pour les erreurs de validation.
+    pub fn param_name(&self) -> Option<&str> {
+        match self {
+            SqlQueryEngineError::ErrorMissingParam { param_name, .. }
+            | SqlQueryEngineError::ErrorUnexpectedParam { param_name, .. }
+            | SqlQueryEngineError::ErrorInvalidParam { param_name, .. }
+            | SqlQueryEngineError::ErrorUnknownParamType { param_name, .. }
+            | SqlQueryEngineError::ErrorConstraintViolation { param_name, .. }
+            | SqlQueryEngineError::ErrorInvalidConstraint { param_name, .. }
+            | SqlQueryEngineError::ErrorRuleViolation { param_name, .. }
+            | SqlQueryEngineError::ErrorInvalidDefaultValue { param_name, .. } => Some(param_name),
+            _ => None,
+        }
+    }
+
+    /// Rapport de validation complet, pour `ErrorValidation`.
+    pub fn validation_report(&self) -> Option<&SqlParamValidationReport> {
+        match self {
+            SqlQueryEngineError::ErrorValidation { report, .. } => Some(report),
+            _ => None,
+        }
+    }
+
+    /// Problèmes détectés lors du chargement de définitions, pour `ErrorLoadQueryDefinitions`.
+    pub fn definition_issues(&self) -> Option<&[SqlDefinitionIssue]> {
+        match self {
+            SqlQueryEngineError::ErrorLoadQueryDefinitions { issues } => Some(issues),
+            _ => None,
+        }
+    }
+
+    /// Erreur `sqlx` d'origine, pour les erreurs provenant de la base de données.
+    pub fn sqlx_error(&self) -> Option<&sqlx::Error> {
+        match self {
+            SqlQueryEngineError::ErrorGetSqlQuery { source, .. }
+            | SqlQueryEngineError::ErrorGetSqlQueryParam { source, .. }
+            | SqlQueryEngineError::ErrorRegisterSqlQuery { source, .. }
+            | SqlQueryEngineError::ErrorRevision { source, .. }
+            | SqlQueryEngineError::ErrorExecutionQuery { source, .. }
+            | SqlQueryEngineError::ErrorReadOnlyViolation { source, .. }
+            | SqlQueryEngineError::ErrorAudit { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// Code d'erreur renvoyé par la base de données (SQLSTATE, ex : `23505` pour une violation d'unicité).
+    pub fn database_code(&self) -> Option<String> {
+        match self.sqlx_error()? {
+            sqlx::Error::Database(e) => e.code().map(|code| code.into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Indique si l'erreur provient d'une base injoignable (connexion impossible, pool fermé ou saturé)
+    /// plutôt que de la requête elle-même.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(
+            self.sqlx_error(),
+            Some(sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::WorkerCrashed)
+        )
+    }
+
+    /// Indique si l'erreur provient de la validation des paramètres, y compris leurs valeurs par défaut
+    /// (résolues à partir du contexte fourni par l'appelant).
+    pub fn is_validation_error(&self) -> bool {
+        matches!(
+            self,
+            SqlQueryEngineError::ErrorMissingParam { .. }
+                | SqlQueryEngineError::ErrorUnexpectedParam { .. }
+                | SqlQueryEngineError::ErrorInvalidParam { .. }
+                | SqlQueryEngineError::ErrorUnknownParamType { .. }
+                | SqlQueryEngineError::ErrorConstraintViolation { .. }
+                | SqlQueryEngineError::ErrorRuleViolation { .. }
+                | SqlQueryEngineError::ErrorInvalidDefaultValue { .. }
+                | SqlQueryEngineError::ErrorValidation { .. }
+        )
+    }
+}
+
+impl fmt::Display for SqlQueryEngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlQueryEngineError::ErrorGetSqlQuery { table, item_key, .. } =>
+                write!(f, "Failed to fetch query on table '{}' with item_key '{}'", table, item_key),
+            SqlQueryEngineError::ErrorNoQueryFound { item_key } =>
+                write!(f, "No query found with item_key '{}'", item_key),
+            SqlQueryEngineError::ErrorLoadQueryDefinitions { issues } => {
+                write!(f, "Failed to load query definitions ({} issue(s))", issues.len())?;
+                issues.iter().try_for_each(|issue| write!(f, "\n  {}", issue))
+            },
+            SqlQueryEngineError::ErrorAccessDenied { item_key } =>
+                write!(f, "Access denied to query '{}'", item_key),
+            SqlQueryEngineError::ErrorQueryUnavailable { item_key, status } =>
+                write!(f, "Query '{}' is not available (status: {})", item_key, status),
+            SqlQueryEngineError::ErrorUnknownDatasource { item_key, datasource } =>
+                write!(f, "Query '{}' targets unknown datasource '{}'", item_key, datasource),
+            SqlQueryEngineError::ErrorGetSqlQueryParam { table, item_key, .. } =>
+                write!(f, "Failed to fetch query parameters on table '{}' with item_key '{}'", table, item_key),
+            SqlQueryEngineError::ErrorRegisterSqlQuery { table, item_key, .. } =>
+                write!(f, "Failed to register query '{}' on table '{}'", item_key, table),
+            SqlQueryEngineError::ErrorRevision { table, item_key, .. } =>
+                write!(f, "Failed to access revisions of query '{}' on table '{}'", item_key, table),
+            SqlQueryEngineError::ErrorRevisionNotFound { item_key, revision } =>
+                write!(f, "No revision {} found for query '{}'", revision, item_key),
+            SqlQueryEngineError::ErrorUnsafeSql { item_key, violation } =>
+                write!(f, "Query '{}' is rejected: {}", item_key, violation),
+            SqlQueryEngineError::ErrorMissingParam { item_key, param_name } =>
+                write!(f, "Required parameter '{}' is missing for query '{}'", param_name, item_key),
+            SqlQueryEngineError::ErrorUnexpectedParam { item_key, param_name } =>
+                write!(f, "Unexpected parameter '{}' provided for query '{}'", param_name, item_key),
+            SqlQueryEngineError::ErrorInvalidParam { item_key, param_name, expected_type, value } =>
+                write!(f, "Parameter '{}' of query '{}' expects a {} value, got '{}'", param_name, item_key, expected_type, value),
+            SqlQueryEngineError::ErrorUnknownParamType { item_key, param_name, param_type } =>
+                write!(f, "Parameter '{}' of query '{}' has an unknown type '{}'", param_name, item_key, param_type),
+            SqlQueryEngineError::ErrorConstraintViolation { item_key, param_name, value, violation } =>
+                write!(f, "Parameter '{}' of query '{}': value '{}' {}", param_name, item_key, value, violation),
+            SqlQueryEngineError::ErrorInvalidConstraint { item_key, param_name, violation } =>
+                write!(f, "Constraints of parameter '{}' of query '{}' are invalid: {}", param_name, item_key, violation),
+            SqlQueryEngineError::ErrorRuleViolation { item_key, rule, .. } =>
+                write!(f, "Query '{}': {}", item_key, rule),
+            SqlQueryEngineError::ErrorInvalidDefaultValue { item_key, param_name, default_value, reason } =>
+                write!(f, "Default value '{}' of parameter '{}' of query '{}' is invalid: {}", default_value, param_name, item_key, reason),
+            SqlQueryEngineError::ErrorValidation { item_key, report } =>
+                write!(f, "{} invalid parameter(s) for query '{}'", report.errors.len(), item_key),
+            SqlQueryEngineError::ErrorExecutionQuery { item_key, .. } =>
+                write!(f, "Error executing query '{}'", item_key),
+            SqlQueryEngineError::ErrorReadOnlyViolation { item_key, .. } =>
+                write!(f, "Query '{}' attempted to write in a read-only transaction", item_key),
+            SqlQueryEngineError::ErrorInvalidContext { item_key, reason } =>
+                write!(f, "Execution context of query '{}' is invalid: {}", item_key, reason),
+            SqlQueryEngineError::ErrorMaskingUnsupported { item_key } =>
+                write!(f, "Query '{}' has masked columns for the caller and must be run as JSON", item_key),
+            SqlQueryEngineError::ErrorMissingHashKey { item_key } =>
+                write!(f, "Query '{}' has hashed columns for the caller but no hash key is configured", item_key),
+            SqlQueryEngineError::ErrorAudit { table, item_key, .. } =>
+                write!(f, "Failed to write audit record of query '{}' on table '{}'", item_key, table),
+            SqlQueryEngineError::ErrorRowLimitExceeded { item_key, max_rows } =>
+                write!(f, "Query '{}' returned more than {} rows", item_key, max_rows),
+            SqlQueryEngineError::ErrorTimeout { item_key, timeout } =>
+                write!(f, "Query '{}' timed out after {:?}", item_key, timeout),
+        }
+    }
+}
+
+impl std::error::Error for SqlQueryEngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.sqlx_error().map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+use crate::sql_safety::SqlStatementKind;
+
+/// Limites appliquées par défaut lors de l'exécution d'une requête dynamique.
+/// # Champs
+/// - `max_rows`: Nombre maximal de lignes acceptées en retour (aucune limite si `None`)
+/// - `timeout`: Durée maximale d'exécution de la requête (aucune limite si `None`)
+/// - `read_only`: Si vrai, la requête est exécutée dans une transaction `START TRANSACTION READ ONLY`
+///   (`PRAGMA query_only` sur SQLite)
+/// - `max_statement_kind`: Nature la plus risquée autorisée pour le code SQL (`Dml` par défaut,
+///   comme `SqlQueryManagerBuilder::max_statement_kind`, qui la remplace pour les exécutions du gestionnaire)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlQueryLimits
+{
+    pub max_rows: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub read_only: bool,
+    pub max_statement_kind: SqlStatementKind,
+}
+
+impl Default for SqlQueryLimits {
+    fn default() -> Self {
+        Self {
+            max_rows: None,
+            timeout: None,
+            read_only: false,
+            max_statement_kind: SqlStatementKind::Dml,
+        }
+    }
+}
+
+impl SqlQueryLimits {
+    /// Crée une nouvelle instance de `SqlQueryLimits`.
+    /// # Arguments
+    /// * `max_rows`: Nombre maximal de lignes acceptées en retour
+    /// * `timeout`: Durée maximale d'exécution de la requête
+    pub fn new(max_rows: Option<usize>, timeout: Option<Duration>) -> Self {
+        Self {
+            max_rows,
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    /// Impose (ou non) l'exécution dans une transaction en lecture seule.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Fixe la nature la plus risquée autorisée pour le code SQL
+    /// (ex : `SqlStatementKind::ReadOnly` pour n'autoriser que les lectures).
+    pub fn with_max_statement_kind(mut self, kind: SqlStatementKind) -> Self {
+        self.max_statement_kind = kind;
+        self
+    }
+
+    /// Crée une instance sans limite de lignes ni de durée (le code SQL reste limité au DML).
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+}
@@ -1,13 +1,39 @@
-// use sqlx::{Pool, FromRow};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 
-use sqlx::FromRow;
+use chrono::Utc;
+
+use sqlx::types::Json;
+use sqlx::{PgConnection, Pool, Postgres};
+use crate::sql_audit::SqlAuditor;
+use crate::sql_backend::SqlBackend;
+use crate::sql_column_policy::{apply_column_policies, SqlColumnPolicy, SqlHashKey};
 use crate::sql_dynamic_query::SqlDynamicQuery;
+use crate::sql_dynamic_query_data::SqlDynamicQueryData;
+use crate::sql_execution_context::ExecutionContext;
+use crate::sql_json_row::SqlJsonRow;
 use crate::sql_query::SqlQuery;
+use crate::sql_import::{SqlImportMode, SqlImportOptions, SqlImportReport};
+use crate::sql_query_cache::SqlQueryCache;
+use crate::sql_query_definition::{SqlDefinitionIssue, SqlQueryDefinition};
+use crate::sql_query_limits::SqlQueryLimits;
+use crate::sql_replica_set::SqlReplicaSet;
+use crate::sql_query_manager_builder::SqlQueryManagerBuilder;
 use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_result::SqlQueryResult;
+use crate::sql_query_revision::SqlQueryRevision;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_registry_document::{SqlExportFilter, SqlRegistryDocument};
+use crate::sql_registry_diff::SqlRegistryDiff;
 use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_safety::SqlStatementKind;
 
 
 /// Cette structure est utilisée pour gérer les requêtes SQL dans la base de données.
+/// Par défaut, les définitions sont lues dans deux tables de la base du pool (voir `PgQueryRepository`
+/// et `SqliteQueryRepository`) ; un autre dépôt (`QueryRepository`) peut être fourni au constructeur,
+/// l'exécution restant sur le pool (ou sur la base nommée par la colonne `datasource` de la requête,
+/// ou sur un réplica pour les lectures, voir `SqlQueryManagerBuilder::replica`).
 /// La table doit obligatoirement contenir les colonnes suivantes :
 /// - `id`: Identifiant unique de la requête
 /// - `name`: Nom de la requête
@@ -15,138 +41,1259 @@ use crate::sql_query_engine_error::SqlQueryEngineError;
 /// - `sql_code`: Code SQL de la requête
 /// - `item_key`: Clé d'élément unique pour identifier la requête
 /// - `sign` : Signature de la requête (optionnelle)
-
-#[derive(Debug, FromRow)]
-pub struct SqlQueryManager<'a>
+///
+/// Le gestionnaire possède son pool de connexions : il est `Clone + Send + Sync + 'static`
+/// et peut être partagé entre tâches ou stocké dans l'état d'un framework web.
+/// Les clones partagent le même cache.
+///
+/// Le gestionnaire fonctionne sur PostgreSQL (par défaut) ou SQLite (voir `SqlBackend`).
+/// L'enregistrement, l'import et les révisions écrivent dans les tables PostgreSQL :
+/// ils ne sont disponibles que sur `SqlQueryManager<Postgres>`.
+pub struct SqlQueryManager<DB: SqlBackend = Postgres>
 {
     /// Pool de connexions à la base de données
-    pool: &'a sqlx::Pool<sqlx::Postgres>,
+    pool: Pool<DB>,
 
     /// Nom de la table SQL contenant les requêtes
     table_query: String,
 
     /// Nom de la table SQL contenant les paramètres de requête
     table_query_params: String,
+
+    /// Nom de la table SQL contenant les révisions des requêtes
+    table_query_revisions: String,
+
+    /// Source des définitions de requêtes
+    repository: Arc<dyn QueryRepository>,
+
+    /// Cache des requêtes dynamiques
+    cache: SqlQueryCache,
+
+    /// Limites appliquées par défaut lors de l'exécution
+    limits: SqlQueryLimits,
+
+    /// Si vrai, `run` renvoie tous les problèmes de validation dans une `ErrorValidation`
+    collect_validation_errors: bool,
+
+    /// Nature la plus risquée autorisée pour le code SQL des requêtes
+    max_statement_kind: SqlStatementKind,
+
+    /// Si vrai, les brouillons sont exécutables
+    allow_drafts: bool,
+
+    /// Audit des exécutions (désactivé si `None`)
+    auditor: Option<SqlAuditor>,
+
+    /// Clé des empreintes des colonnes masquées par une politique `hash`
+    hash_key: Option<SqlHashKey>,
+
+    /// Bases nommées, désignées par la colonne `datasource` des requêtes
+    datasources: Arc<HashMap<String, Pool<DB>>>,
+
+    /// Réplicas en lecture du pool principal
+    replicas: SqlReplicaSet<DB>,
 }
 
-impl <'a> SqlQueryManager<'a> {
+impl<DB: SqlBackend> Clone for SqlQueryManager<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table_query: self.table_query.clone(),
+            table_query_params: self.table_query_params.clone(),
+            table_query_revisions: self.table_query_revisions.clone(),
+            repository: self.repository.clone(),
+            cache: self.cache.clone(),
+            limits: self.limits,
+            collect_validation_errors: self.collect_validation_errors,
+            max_statement_kind: self.max_statement_kind,
+            allow_drafts: self.allow_drafts,
+            auditor: self.auditor.clone(),
+            hash_key: self.hash_key.clone(),
+            datasources: self.datasources.clone(),
+            replicas: self.replicas.clone(),
+        }
+    }
+}
+
+impl<DB: SqlBackend> std::fmt::Debug for SqlQueryManager<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlQueryManager")
+            .field("backend", &<DB as sqlx::Database>::NAME)
+            .field("pool", &self.pool)
+            .field("table_query", &self.table_query)
+            .field("table_query_params", &self.table_query_params)
+            .field("table_query_revisions", &self.table_query_revisions)
+            .field("repository", &self.repository)
+            .field("cache", &self.cache)
+            .field("limits", &self.limits)
+            .field("collect_validation_errors", &self.collect_validation_errors)
+            .field("max_statement_kind", &self.max_statement_kind)
+            .field("allow_drafts", &self.allow_drafts)
+            .field("auditor", &self.auditor)
+            .field("hash_key", &self.hash_key)
+            .field("datasources", &self.datasources)
+            .field("replicas", &self.replicas)
+            .finish()
+    }
+}
 
-    /// Crée une nouvelle instance de `SqlQueryManager`.
+impl<DB: SqlBackend> SqlQueryManager<DB> {
+
+    /// Crée une nouvelle instance de `SqlQueryManager` à partir d'un pool emprunté.
+    /// Le pool est cloné (un `Pool` n'est qu'une référence partagée vers les connexions).
     /// # Arguments
     /// * `pool`: Pool de connexions à la base de données
     /// * `table_query`: Nom de la table SQL contenant les requêtes
     /// * `table_query_params`: Nom de la table SQL contenant les paramètres de requête
-    
-    pub fn new(pool: &'a sqlx::Pool<sqlx::Postgres>, table_query : String, table_query_params : String) -> Self {
-        Self { 
-            pool,
+    pub fn new(pool: &Pool<DB>, table_query : String, table_query_params : String) -> Self {
+        Self::builder(pool.clone())
+            .table_query(table_query)
+            .table_query_params(table_query_params)
+            .build()
+    }
+
+    /// Crée un constructeur permettant de configurer les tables, le schéma, le cache et les limites.
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base de données
+    pub fn builder(pool: Pool<DB>) -> SqlQueryManagerBuilder<DB> {
+        SqlQueryManagerBuilder::new(pool)
+    }
+
+    pub(crate) fn from_builder(builder: SqlQueryManagerBuilder<DB>) -> Self {
+        let (table_query, table_query_params, table_query_revisions) = match &builder.schema {
+            Some(schema) => (
+                format!("{}.{}", schema, builder.table_query),
+                format!("{}.{}", schema, builder.table_query_params),
+                format!("{}.{}", schema, builder.table_query_revisions),
+            ),
+            None => (builder.table_query, builder.table_query_params, builder.table_query_revisions),
+        };
+
+        let repository = builder.repository.unwrap_or_else(||
+            DB::query_repository(builder.pool.clone(), table_query.clone(), table_query_params.clone())
+        );
+
+        Self {
+            pool: builder.pool,
             table_query,
             table_query_params,
+            table_query_revisions,
+            repository,
+            cache: SqlQueryCache::new(builder.cache_ttl, builder.cache_capacity),
+            limits: builder.limits,
+            collect_validation_errors: builder.collect_validation_errors,
+            max_statement_kind: builder.max_statement_kind,
+            allow_drafts: builder.allow_drafts,
+            auditor: builder.auditor,
+            hash_key: builder.hash_key,
+            datasources: Arc::new(builder.datasources),
+            replicas: SqlReplicaSet::new(builder.replicas, builder.replica_retry_after),
         }
     }
-    
-   /// Récupère une requête par son item_key
-   /// # Arguments
-   /// * `item_key`: Clé d'élément unique pour identifier la requête
-   
-   pub async fn get_sql_query_by_item_key(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
-   {
-        let query  = format!(
-            "SELECT * FROM {} WHERE item_key = $1",
-            self.table_query
-        );
 
-        match sqlx::query_as::<sqlx::Postgres, SqlQuery>(query.as_str())
-            .bind(item_key)
-            .fetch_optional(self.pool)
-            .await
-        {
-            Ok(query) => {
-                if query.is_none() {
-                    return Err(SqlQueryEngineError::ErrorNoQueryFound(format!("get_query_by_item_key : Failed to fetch query with item_key '{}': {}", item_key, "not found")));
-                }
-                Ok(query)
-            },
-            Err(e) => Err
-            (
-                SqlQueryEngineError::ErrorGetSqlQuery(format!("get_query_by_item_key : Failed to fetch query on table '{}' with item_key {} : {}", self.table_query, item_key, e))
-            ),
+    /// Retourne le pool de connexions du gestionnaire.
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    /// Retourne le pool d'une base nommée (voir `SqlQueryManagerBuilder::datasource`).
+    /// # Arguments
+    /// * `name`: Nom de la base
+    pub fn datasource(&self, name: &str) -> Option<&Pool<DB>> {
+        self.datasources.get(name)
+    }
+
+    /// Retourne les réplicas en lecture du pool principal (voir `SqlQueryManagerBuilder::replica`).
+    pub fn replicas(&self) -> &SqlReplicaSet<DB> {
+        &self.replicas
+    }
+
+    /// Indique si une requête de cette nature peut être exécutée sur un réplica :
+    /// lecture seule, sans `needs_fresh_data` ni base nommée.
+    /// # Arguments
+    /// * `query`: La requête
+    /// * `kind`: Nature de son code SQL (voir `SqlQuery::check_safety`)
+    pub fn is_replica_eligible(&self, query: &SqlQuery, kind: SqlStatementKind) -> bool {
+        kind == SqlStatementKind::ReadOnly && !query.needs_fresh_data() && query.datasource().is_none()
+    }
+
+    /// Retourne le pool sur lequel la requête est exécutée : celui de sa base nommée, sinon le pool principal.
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorUnknownDatasource)`: Si la requête désigne une base inconnue du gestionnaire
+    pub fn pool_for(&self, query: &SqlQuery) -> Result<&Pool<DB>, SqlQueryEngineError> {
+        match query.datasource() {
+            None => Ok(&self.pool),
+            Some(datasource) => self.datasources.get(datasource).ok_or_else(|| SqlQueryEngineError::ErrorUnknownDatasource {
+                item_key: query.item_key.clone(),
+                datasource: datasource.to_string(),
+            }),
         }
     }
 
-    
-    /// Récupère les paramètres d'une requête SQL par son item_key
+    /// Retourne le nom (qualifié) de la table contenant les requêtes.
+    pub fn table_query(&self) -> &str {
+        &self.table_query
+    }
+
+    /// Retourne le nom (qualifié) de la table contenant les paramètres de requête.
+    pub fn table_query_params(&self) -> &str {
+        &self.table_query_params
+    }
+
+    /// Retourne le nom (qualifié) de la table contenant les révisions des requêtes.
+    pub fn table_query_revisions(&self) -> &str {
+        &self.table_query_revisions
+    }
+
+    /// Retourne la source des définitions de requêtes.
+    pub fn repository(&self) -> &Arc<dyn QueryRepository> {
+        &self.repository
+    }
+
+    /// Retourne le cache des requêtes dynamiques.
+    pub fn cache(&self) -> &SqlQueryCache {
+        &self.cache
+    }
+
+    /// Retourne les limites appliquées par défaut lors de l'exécution.
+    pub fn limits(&self) -> SqlQueryLimits {
+        self.limits
+    }
+
+    /// Retourne la nature la plus risquée autorisée pour le code SQL des requêtes.
+    pub fn max_statement_kind(&self) -> SqlStatementKind {
+        self.max_statement_kind
+    }
+
+    /// Retourne l'audit des exécutions, s'il est actif.
+    pub fn auditor(&self) -> Option<&SqlAuditor> {
+        self.auditor.as_ref()
+    }
+
+    /// Retire une requête du cache, par exemple après sa modification.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub fn invalidate_cache(&self, item_key: &str) {
+        self.cache.invalidate(item_key);
+    }
+
+    /// Récupère une requête par son item_key
     /// # Arguments
     /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// # Returns
+    /// * `Ok(Some(SqlQuery))`: Si la requête est trouvée
+    /// * `Ok(None)`: Si aucune requête n'est trouvée avec cette item_key
+    /// * `Err(SqlQueryEngineError)`: En cas d'erreur lors de la récupération
+    pub async fn get_sql_query_by_item_key(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        self.repository.get_query(item_key).await
+    }
+
+    /// Récupère une requête par son item_key, une requête absente étant une erreur.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorNoQueryFound)`: Si aucune requête n'est trouvée avec cette item_key
+    pub async fn require_sql_query_by_item_key(&self, item_key: &str) -> Result<SqlQuery, SqlQueryEngineError>
+    {
+        self.get_sql_query_by_item_key(item_key).await?
+            .ok_or_else(|| Self::no_query_found(item_key))
+    }
+
+    /// Récupère les paramètres d'une requête SQL par son item_key, triés par `param_order`
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// # Returns
+    /// * `Ok(Some(params))`: Si la requête existe (`params` est vide si elle n'a aucun paramètre)
+    /// * `Ok(None)`: Si aucune requête n'est trouvée avec cette item_key
+    /// * `Err(SqlQueryEngineError)`: En cas d'erreur lors de la récupération
     pub async fn get_sql_query_params_by_item_key(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
     {
-        let query = format!(
-            r#"
-                SELECT
-                    qp.id,
-                    qp.param_name,
-                    qp.param_type,
-                    qp.param_order,
-                    qp.is_required,
-                    qp.default_value,
-                    qp.description,
-                    qp.item_key
-                FROM {} qp
-                INNER JOIN {} q ON qp.item_key = q.item_key
-                WHERE qp.item_key = $1
-            "#,
-            self.table_query_params,
-            self.table_query
-        );
+        self.repository.get_params(item_key).await
+    }
 
-        match sqlx::query_as::<sqlx::Postgres, SqlQueryParam>(query.as_str())
-            .bind(item_key)
-            .fetch_all(self.pool)
-            .await
-        {
-            Ok(mut params) =>
-            {
-                if params.is_empty()
-                {
-                    Ok(None)
-                } 
-                else
-                {
-                    params.sort_by_key(|p| p.param_order);
-                    Ok(Some(params))
-                }
-            },
-            Err(e) => Err(SqlQueryEngineError::ErrorGetSqlQueryParam(
-                format!("get_sql_query_params_by_item_key : Failed to fetch query parameters on table '{}' with item_key '{}': {}", 
-                    self.table_query_params, item_key, e)
-            )),
-        }
+    /// Récupère les paramètres d'une requête SQL par son item_key, une requête absente étant une erreur.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// # Returns
+    /// * `Ok(params)`: Les paramètres de la requête (vide si elle n'a aucun paramètre)
+    /// * `Err(SqlQueryEngineError::ErrorNoQueryFound)`: Si aucune requête n'est trouvée avec cette item_key
+    pub async fn require_sql_query_params_by_item_key(&self, item_key: &str) -> Result<Vec<SqlQueryParam>, SqlQueryEngineError>
+    {
+        self.get_sql_query_params_by_item_key(item_key).await?
+            .ok_or_else(|| Self::no_query_found(item_key))
     }
 
-    /// Récupère une requête dynamique complète (requête + paramètres) par son item_key
+    /// Indique si une requête existe pour cette item_key
     /// # Arguments
     /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub async fn sql_query_exists(&self, item_key: &str) -> Result<bool, SqlQueryEngineError>
+    {
+        Ok(self.repository.get_query(item_key).await?.is_some())
+    }
+
+    /// Récupère une requête dynamique complète (requête + paramètres) par son item_key,
+    /// si les rôles de l'appelant y donnent accès (voir `SqlQuery::is_accessible_by`).
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// * `roles`: Rôles ou permissions de l'appelant
     /// # Returns
-    /// * `Ok(Some(SqlDynamicQuery))`: Si la requête est trouvée
+    /// * `Ok(Some(SqlDynamicQuery))`: Si la requête est trouvée et accessible
     /// * `Ok(None)`: Si aucune requête n'est trouvée avec cette item_key
+    /// * `Err(SqlQueryEngineError::ErrorAccessDenied)`: Si la requête existe mais n'est pas accessible
+    /// * `Err(SqlQueryEngineError::ErrorQueryUnavailable)`: Si la requête est un brouillon
+    ///   (sauf avec `allow_drafts`), est désactivée ou a atteint sa date `sunset_at`
     /// * `Err(SqlQueryEngineError)`: En cas d'erreur lors de la récupération
-    
-    pub async fn get_sql_dynamic_query(&self, item_key: &str) -> Result<Option<SqlDynamicQuery>, SqlQueryEngineError>
+    ///
+    /// Si le cache est actif, la requête est d'abord recherchée dans le cache.
+    pub async fn get_sql_dynamic_query(&self, item_key: &str, roles: &[String]) -> Result<Option<SqlDynamicQuery>, SqlQueryEngineError>
+    {
+        match self.get_sql_dynamic_query_any_status(item_key).await? {
+            Some(dynamic_query) => self.authorize(dynamic_query, roles).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Récupère une requête dynamique complète par son item_key, une requête absente étant une erreur.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// * `roles`: Rôles ou permissions de l'appelant
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorNoQueryFound)`: Si aucune requête n'est trouvée avec cette item_key
+    pub async fn require_sql_dynamic_query(&self, item_key: &str, roles: &[String]) -> Result<SqlDynamicQuery, SqlQueryEngineError>
     {
+        self.get_sql_dynamic_query(item_key, roles).await?
+            .ok_or_else(|| Self::no_query_found(item_key))
+    }
+
+    /// **Attention : les rôles ne sont pas vérifiés.** Identique à `get_sql_dynamic_query` pour un appelant
+    /// possédant tous les rôles : réservé aux outils d'administration, le code SQL, les paramètres
+    /// et les politiques de masquage des requêtes restreintes étant renvoyés à qui l'appelle.
+    /// Le statut de la requête est vérifié.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub async fn get_sql_dynamic_query_unchecked(&self, item_key: &str) -> Result<Option<SqlDynamicQuery>, SqlQueryEngineError>
+    {
+        match self.get_sql_dynamic_query_any_status(item_key).await? {
+            Some(dynamic_query) => self.check_status(dynamic_query).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// **Attention : ni les rôles ni le statut ne sont vérifiés.** Identique à `get_sql_dynamic_query_unchecked`,
+    /// quel que soit le statut de la requête (par exemple pour afficher un brouillon ou une requête désactivée
+    /// dans un outil d'administration).
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub async fn get_sql_dynamic_query_any_status(&self, item_key: &str) -> Result<Option<SqlDynamicQuery>, SqlQueryEngineError>
+    {
+        if let Some(cached) = self.cache.get(item_key) {
+            return Ok(Some(cached));
+        }
+
         // Récupérer la requête SQL
         let query = match self.get_sql_query_by_item_key(item_key).await? {
             Some(q) => q,
             None => return Ok(None), // Pas de requête trouvée
         };
 
-        // Récupérer les paramètres (peut être None si pas de paramètres)
-        let params = self.get_sql_query_params_by_item_key(item_key).await?;
+        // Récupérer les paramètres, `None` signifiant que la requête n'en a aucun
+        let params = self.get_sql_query_params_by_item_key(item_key).await?
+            .filter(|params| !params.is_empty());
 
-        // Construire et retourner la SqlDynamicQuery
-        Ok(Some(SqlDynamicQuery {
+        // Construire la SqlDynamicQuery et la conserver dans le cache
+        let dynamic_query = SqlDynamicQuery {
             query,
             params,
-        }))
+        };
+        self.cache.insert(dynamic_query.clone());
+
+        Ok(Some(dynamic_query))
+    }
+
+    /// Liste les item_keys que des appelants possédant ces rôles peuvent exécuter (statut compris), triées par item_key
+    /// (par exemple pour masquer les rapports indisponibles dans une interface).
+    /// # Arguments
+    /// * `roles`: Rôles ou permissions de l'appelant
+    pub async fn list_authorized_item_keys(&self, roles: &[String]) -> Result<Vec<String>, SqlQueryEngineError>
+    {
+        let queries = self.repository.list().await?;
+        let now = Utc::now();
+
+        Ok(queries.into_iter()
+            .filter(|query| query.is_accessible_by(roles) && query.status_at(now).is_runnable(self.allow_drafts))
+            .map(|query| query.item_key)
+            .collect())
+    }
+
+    /// Exporte les requêtes sélectionnées par le filtre et leurs paramètres, lues dans le dépôt du gestionnaire.
+    /// Le document se sérialise en YAML ou JSON (`to_yaml`, `to_json`) et se réimporte avec `import`.
+    /// # Arguments
+    /// * `filter`: Sélection des requêtes (`SqlExportFilter::all()` pour tout exporter)
+    pub async fn export(&self, filter: &SqlExportFilter) -> Result<SqlRegistryDocument, SqlQueryEngineError>
+    {
+        let mut queries = Vec::new();
+        for query in self.repository.list().await? {
+            if !filter.matches(&query.item_key) {
+                continue;
+            }
+            let params = self.repository.get_params(&query.item_key).await?.unwrap_or_default();
+            queries.push(SqlQueryDefinition::from_parts(&query, &params));
+        }
+
+        Ok(SqlRegistryDocument::new(filter.clone(), queries))
+    }
+
+    /// Compare les requêtes du gestionnaire (source) à celles d'un autre registre (cible) :
+    /// un autre gestionnaire (`other.repository().as_ref()`), des fichiers ou un document exporté
+    /// (`&MemoryQueryRepository::from(document)`).
+    /// # Arguments
+    /// * `to`: Registre cible
+    pub async fn diff(&self, to: &dyn QueryRepository) -> Result<SqlRegistryDiff, SqlQueryEngineError>
+    {
+        SqlRegistryDiff::between(self.repository.as_ref(), to).await
+    }
+
+}
+
+impl SqlQueryManager {
+
+    /// Enregistre une nouvelle requête et ses paramètres dans les tables du gestionnaire (quel que soit son dépôt),
+    /// dans une transaction, après avoir vérifié son code SQL
+    /// (une seule instruction, de nature autorisée par le gestionnaire et par `statement_kind`)
+    /// et les contraintes de ses paramètres. Sa première révision active est ajoutée à l'historique dans la même transaction
+    /// (la table des révisions doit exister, voir `create_revisions_table`).
+    /// Les colonnes optionnelles (`rules`, `allowed_roles`, `statement_kind`, `read_only`, `column_policies`, `status`, `sunset_at`, `datasource`, `needs_fresh_data`, `constraints`, `sensitive`)
+    /// ne sont renseignées que si elles ont une valeur.
+    /// # Arguments
+    /// * `query`: La requête à enregistrer (`id` est ignoré)
+    /// * `params`: Les paramètres de la requête (`id` et `item_key` sont ignorés)
+    /// * `author`: Auteur de la révision
+    /// # Returns
+    /// * `Ok(SqlStatementKind)`: La nature du code SQL enregistré
+    /// * `Err(SqlQueryEngineError::ErrorUnsafeSql)`: Si le code SQL est refusé
+    /// * `Err(SqlQueryEngineError::ErrorInvalidConstraint)`: Si les contraintes d'un paramètre sont invalides
+    /// * `Err(SqlQueryEngineError::ErrorRegisterSqlQuery)`: Si l'écriture échoue (ex : item_key déjà utilisée)
+    pub async fn register_sql_query(&self, query: &SqlQuery, params: &[SqlQueryParam], author: &str) -> Result<SqlStatementKind, SqlQueryEngineError>
+    {
+        let kind = query.check_safety(self.max_statement_kind, Postgres::sql_dialect())?;
+        for param in params {
+            if let Some(Err(violation)) = param.constraints.as_ref().map(|constraints| constraints.validate()) {
+                return Err(SqlQueryEngineError::ErrorInvalidConstraint {
+                    item_key: query.item_key.clone(),
+                    param_name: param.param_name.clone(),
+                    violation,
+                });
+            }
+        }
+
+        let mut transaction = self.pool.begin().await.map_err(Self::register_error(&self.table_query, &query.item_key))?;
+        self.lock_item_key(&mut transaction, &query.item_key).await?;
+        self.insert_sql_query(&mut transaction, query, params).await?;
+        self.insert_revision(&mut transaction, &SqlQueryDefinition::from_parts(query, params), author, None).await?;
+        transaction.commit().await.map_err(Self::register_error(&self.table_query, &query.item_key))?;
+        self.cache.invalidate(&query.item_key);
+
+        Ok(kind)
+    }
+
+    /// Insère une requête et ses paramètres, les colonnes optionnelles n'étant renseignées que si elles ont une valeur.
+    async fn insert_sql_query(&self, connection: &mut PgConnection, query: &SqlQuery, params: &[SqlQueryParam]) -> Result<(), SqlQueryEngineError>
+    {
+        let register_error = Self::register_error(&self.table_query, &query.item_key);
+        let optional_columns: Vec<&OptionalQueryColumn> = OPTIONAL_QUERY_COLUMNS.iter().filter(|column| (column.is_set)(query)).collect();
+        let mut columns = vec!["name", "description", "sql_code", "item_key", "sign"];
+        columns.extend(optional_columns.iter().map(|column| column.name));
+        let insert_query = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            self.table_query,
+            columns.join(", "),
+            placeholders(columns.len())
+        );
+
+        let mut insert = sqlx::query(insert_query.as_str())
+            .bind(&query.name)
+            .bind(&query.description)
+            .bind(&query.sql_code)
+            .bind(&query.item_key)
+            .bind(&query.sign);
+        for column in &optional_columns {
+            insert = (column.bind)(insert, query);
+        }
+        insert.execute(&mut *connection).await.map_err(register_error)?;
+
+        self.insert_sql_query_params(connection, &query.item_key, params).await
+    }
+
+    /// Insère les paramètres d'une requête.
+    async fn insert_sql_query_params(&self, connection: &mut PgConnection, item_key: &str, params: &[SqlQueryParam]) -> Result<(), SqlQueryEngineError>
+    {
+        for param in params {
+            let mut columns = vec!["item_key", "param_name", "param_type", "param_order", "is_required", "default_value", "description"];
+            if param.constraints.is_some() {
+                columns.push("constraints");
+            }
+            if param.sensitive.is_some() {
+                columns.push("sensitive");
+            }
+            let insert_query = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                self.table_query_params,
+                columns.join(", "),
+                placeholders(columns.len())
+            );
+
+            let mut insert = sqlx::query(insert_query.as_str())
+                .bind(item_key)
+                .bind(&param.param_name)
+                .bind(&param.param_type)
+                .bind(param.param_order)
+                .bind(param.is_required)
+                .bind(&param.default_value)
+                .bind(&param.description);
+            if let Some(constraints) = &param.constraints {
+                insert = insert.bind(constraints);
+            }
+            if let Some(sensitive) = param.sensitive {
+                insert = insert.bind(sensitive);
+            }
+            insert.execute(&mut *connection).await.map_err(Self::register_error(&self.table_query_params, item_key))?;
+        }
+
+        Ok(())
+    }
+
+    /// Met à jour une requête existante et remplace ses paramètres.
+    /// Une colonne optionnelle n'est écrite que si la nouvelle ou l'ancienne valeur est renseignée
+    /// (elle existe alors forcément dans la table).
+    async fn update_sql_query(&self, connection: &mut PgConnection, current: &SqlQuery, query: &SqlQuery, params: &[SqlQueryParam]) -> Result<(), SqlQueryEngineError>
+    {
+        let optional_columns: Vec<&OptionalQueryColumn> = OPTIONAL_QUERY_COLUMNS.iter()
+            .filter(|column| (column.is_set)(query) || (column.is_set)(current))
+            .collect();
+        let mut columns = vec!["name", "description", "sql_code", "sign"];
+        columns.extend(optional_columns.iter().map(|column| column.name));
+        let update_query = format!(
+            "UPDATE {} SET {} WHERE item_key = ${}",
+            self.table_query,
+            columns.iter().zip(1..).map(|(column, i)| format!("{} = ${}", column, i)).collect::<Vec<_>>().join(", "),
+            columns.len() + 1
+        );
+
+        let mut update = sqlx::query(update_query.as_str())
+            .bind(&query.name)
+            .bind(&query.description)
+            .bind(&query.sql_code)
+            .bind(&query.sign);
+        for column in &optional_columns {
+            update = (column.bind)(update, query);
+        }
+        update.bind(&query.item_key)
+            .execute(&mut *connection)
+            .await
+            .map_err(Self::register_error(&self.table_query, &query.item_key))?;
+
+        self.delete_sql_query_params(connection, &query.item_key).await?;
+        self.insert_sql_query_params(connection, &query.item_key, params).await
+    }
+
+    /// Supprime une requête et ses paramètres.
+    async fn delete_sql_query(&self, connection: &mut PgConnection, item_key: &str) -> Result<(), SqlQueryEngineError>
+    {
+        self.delete_sql_query_params(connection, item_key).await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE item_key = $1", self.table_query))
+            .bind(item_key)
+            .execute(&mut *connection)
+            .await
+            .map_err(Self::register_error(&self.table_query, item_key))?;
+        Ok(())
+    }
+
+    async fn delete_sql_query_params(&self, connection: &mut PgConnection, item_key: &str) -> Result<(), SqlQueryEngineError>
+    {
+        sqlx::query(&format!("DELETE FROM {} WHERE item_key = $1", self.table_query_params))
+            .bind(item_key)
+            .execute(&mut *connection)
+            .await
+            .map_err(Self::register_error(&self.table_query_params, item_key))?;
+        Ok(())
+    }
+
+    /// Lit toutes les requêtes des tables du gestionnaire et leurs paramètres, par item_key.
+    async fn read_registry(&self, connection: &mut PgConnection) -> Result<BTreeMap<String, (SqlQuery, Vec<SqlQueryParam>)>, SqlQueryEngineError>
+    {
+        let queries = sqlx::query_as::<sqlx::Postgres, SqlQuery>(&format!("SELECT * FROM {}", self.table_query))
+            .fetch_all(&mut *connection)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQuery {
+                table: self.table_query.clone(),
+                item_key: String::new(),
+                source,
+            })?;
+        let params = sqlx::query_as::<sqlx::Postgres, SqlQueryParam>(&format!("SELECT * FROM {} ORDER BY param_order", self.table_query_params))
+            .fetch_all(&mut *connection)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQueryParam {
+                table: self.table_query_params.clone(),
+                item_key: String::new(),
+                source,
+            })?;
+
+        let mut registry: BTreeMap<String, (SqlQuery, Vec<SqlQueryParam>)> = queries.into_iter()
+            .map(|query| (query.item_key.clone(), (query, Vec::new())))
+            .collect();
+        for param in params {
+            if let Some((_, query_params)) = registry.get_mut(&param.item_key) {
+                query_params.push(param);
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Importe un document dans les tables du gestionnaire, en une seule transaction.
+    /// Chaque requête créée ou mise à jour reçoit une nouvelle révision active (commentaire `import (<mode>)`),
+    /// les révisions d'une requête supprimée sont désactivées (voir `save_revision`).
+    /// Toutes les définitions sont vérifiées avant la moindre écriture (voir `SqlQueryDefinition::validate`),
+    /// y compris la nature du code SQL autorisée par le gestionnaire.
+    /// Une requête est inchangée si sa définition (requête et paramètres) est identique à celle du document.
+    /// # Arguments
+    /// * `document`: Le document à importer
+    /// * `options`: Mode d'import (un `SqlImportMode`) ou `SqlImportOptions::dry_run(mode)`,
+    ///   qui n'écrit rien et décrit exactement ce que ferait ce mode
+    /// * `author`: Auteur des révisions créées
+    /// # Returns
+    /// * `Ok(SqlImportReport)`: Les item_keys créées, mises à jour, supprimées, inchangées et ignorées
+    /// * `Err(SqlQueryEngineError::ErrorLoadQueryDefinitions)`: Si des définitions sont invalides ou en double
+    /// * `Err(SqlQueryEngineError::ErrorRegisterSqlQuery)`: Si une écriture échoue (rien n'est alors importé)
+    pub async fn import(&self, document: &SqlRegistryDocument, options: impl Into<SqlImportOptions>, author: &str) -> Result<SqlImportReport, SqlQueryEngineError>
+    {
+        let options = options.into();
+        let mode = options.mode;
+        let mut definitions: Vec<&SqlQueryDefinition> = document.queries.iter().collect();
+        definitions.sort_by(|a, b| a.item_key.cmp(&b.item_key));
+
+        let mut issues = Vec::new();
+        for (index, definition) in definitions.iter().enumerate() {
+            issues.extend(self.check_definition(definition));
+            if index > 0 && definitions[index - 1].item_key == definition.item_key {
+                issues.push(SqlDefinitionIssue {
+                    item_key: definition.item_key.clone(),
+                    field: Some("item_key".to_string()),
+                    ..SqlDefinitionIssue::file(String::new(), "item_key is defined twice in the document")
+                });
+            }
+        }
+        if !issues.is_empty() {
+            return Err(SqlQueryEngineError::ErrorLoadQueryDefinitions { issues });
+        }
+
+        let write = !options.dry_run;
+        let mut transaction = self.pool.begin().await.map_err(Self::register_error(&self.table_query, ""))?;
+        let registry = self.read_registry(&mut transaction).await?;
+        let mut report = SqlImportReport::new(options);
+        let comment = format!("import ({})", mode);
+
+        for definition in &definitions {
+            let (query, params) = definition.to_parts();
+            match registry.get(&definition.item_key) {
+                None => {
+                    if write {
+                        self.write_revision(&mut transaction, definition, author, Some(&comment)).await?;
+                    }
+                    report.created.push(definition.item_key.clone());
+                },
+                Some((current, current_params)) if SqlQueryDefinition::from_parts(current, current_params) == SqlQueryDefinition::from_parts(&query, &params) =>
+                    report.unchanged.push(definition.item_key.clone()),
+                Some(_) if mode == SqlImportMode::InsertOnly =>
+                    report.skipped.push(definition.item_key.clone()),
+                Some(_) => {
+                    if write {
+                        self.write_revision(&mut transaction, definition, author, Some(&comment)).await?;
+                    }
+                    report.updated.push(definition.item_key.clone());
+                },
+            }
+        }
+
+        if mode == SqlImportMode::Sync {
+            let imported: HashSet<&str> = definitions.iter().map(|definition| definition.item_key.as_str()).collect();
+            for item_key in registry.keys().filter(|item_key| document.filter.matches(item_key) && !imported.contains(item_key.as_str())) {
+                if write {
+                    self.lock_item_key(&mut transaction, item_key).await?;
+                    self.delete_sql_query(&mut transaction, item_key).await?;
+                    // Les numéros de révision commencent à 1 : toutes les révisions sont désactivées
+                    self.deactivate_other_revisions(&mut transaction, item_key, 0).await?;
+                }
+                report.deleted.push(item_key.clone());
+            }
+        }
+
+        if write {
+            transaction.commit().await.map_err(Self::register_error(&self.table_query, ""))?;
+            for item_key in report.created.iter().chain(&report.updated).chain(&report.deleted) {
+                self.cache.invalidate(item_key);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Vérifie une définition avant de l'écrire : `SqlQueryDefinition::validate`, puis nature du code SQL
+    /// autorisée par le gestionnaire.
+    fn check_definition(&self, definition: &SqlQueryDefinition) -> Vec<SqlDefinitionIssue>
+    {
+        let issues = definition.validate(Postgres::sql_dialect());
+        if !issues.is_empty() {
+            return issues;
+        }
+
+        match definition.to_parts().0.check_safety(self.max_statement_kind, Postgres::sql_dialect()) {
+            Err(SqlQueryEngineError::ErrorUnsafeSql { violation, .. }) => vec![SqlDefinitionIssue {
+                item_key: definition.item_key.clone(),
+                field: Some("sql_code".to_string()),
+                ..SqlDefinitionIssue::file(String::new(), violation)
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Crée la table des révisions si elle n'existe pas.
+    pub async fn create_revisions_table(&self) -> Result<(), SqlQueryEngineError>
+    {
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id BIGSERIAL PRIMARY KEY,
+                item_key TEXT NOT NULL,
+                revision INTEGER NOT NULL,
+                definition JSONB NOT NULL,
+                author TEXT NOT NULL,
+                comment TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                active BOOLEAN NOT NULL DEFAULT false,
+                UNIQUE (item_key, revision)
+            )",
+            self.table_query_revisions
+        );
+
+        sqlx::query(query.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(self.revision_error(""))?;
+        Ok(())
+    }
+
+    /// Enregistre une nouvelle révision d'une requête et la rend active : la définition est vérifiée
+    /// (comme pour `import`), ajoutée à l'historique puis recopiée dans les tables des requêtes et des paramètres,
+    /// dans une seule transaction.
+    /// # Arguments
+    /// * `definition`: La nouvelle définition de la requête
+    /// * `author`: Auteur de la modification
+    /// * `comment`: Commentaire de la modification
+    /// # Returns
+    /// * `Ok(SqlQueryRevision)`: La révision créée (numérotée à partir de 1 pour chaque item_key)
+    /// * `Err(SqlQueryEngineError::ErrorLoadQueryDefinitions)`: Si la définition est invalide
+    /// * `Err(SqlQueryEngineError::ErrorRevision | ErrorRegisterSqlQuery)`: Si une écriture échoue
+    pub async fn save_revision(&self, definition: &SqlQueryDefinition, author: &str, comment: Option<&str>) -> Result<SqlQueryRevision, SqlQueryEngineError>
+    {
+        let issues = self.check_definition(definition);
+        if !issues.is_empty() {
+            return Err(SqlQueryEngineError::ErrorLoadQueryDefinitions { issues });
+        }
+
+        let item_key = definition.item_key.as_str();
+        let mut transaction = self.pool.begin().await.map_err(self.revision_error(item_key))?;
+        let revision = self.write_revision(&mut transaction, definition, author, comment).await?;
+
+        transaction.commit().await.map_err(self.revision_error(item_key))?;
+        self.cache.invalidate(item_key);
+
+        Ok(revision)
+    }
+
+    /// Écrit une définition dans les tables (création ou mise à jour) et l'ajoute à l'historique comme révision active,
+    /// l'item_key étant verrouillée jusqu'à la fin de la transaction.
+    async fn write_revision(&self, connection: &mut PgConnection, definition: &SqlQueryDefinition, author: &str, comment: Option<&str>) -> Result<SqlQueryRevision, SqlQueryEngineError>
+    {
+        self.lock_item_key(connection, &definition.item_key).await?;
+        self.apply_definition(connection, definition).await?;
+        self.insert_revision(connection, definition, author, comment).await
+    }
+
+    /// Verrouille une item_key jusqu'à la fin de la transaction (verrou consultatif) : deux enregistrements
+    /// simultanés d'une même requête, y compris la première révision d'une item_key nouvelle,
+    /// sont exécutés l'un après l'autre et ne reçoivent pas le même numéro de révision.
+    async fn lock_item_key(&self, connection: &mut PgConnection, item_key: &str) -> Result<(), SqlQueryEngineError>
+    {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(format!("{}:{}", self.table_query_revisions, item_key))
+            .execute(&mut *connection)
+            .await
+            .map_err(self.revision_error(item_key))?;
+        Ok(())
+    }
+
+    /// Ajoute une révision active à l'historique et désactive les autres révisions de l'item_key.
+    async fn insert_revision(&self, connection: &mut PgConnection, definition: &SqlQueryDefinition, author: &str, comment: Option<&str>) -> Result<SqlQueryRevision, SqlQueryEngineError>
+    {
+        let item_key = definition.item_key.as_str();
+        let insert_query = format!(
+            r#"
+                INSERT INTO {0} (item_key, revision, definition, author, comment, active)
+                SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4, true
+                FROM {0}
+                WHERE item_key = $1
+                RETURNING item_key, revision, definition, author, comment, created_at, active
+            "#,
+            self.table_query_revisions
+        );
+        let revision = sqlx::query_as::<sqlx::Postgres, SqlQueryRevision>(insert_query.as_str())
+            .bind(item_key)
+            .bind(Json(definition))
+            .bind(author)
+            .bind(comment)
+            .fetch_one(&mut *connection)
+            .await
+            .map_err(self.revision_error(item_key))?;
+        self.deactivate_other_revisions(connection, item_key, revision.revision).await?;
+
+        Ok(revision)
+    }
+
+    /// Historique des révisions d'une requête, de la plus ancienne à la plus récente.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    pub async fn query_history(&self, item_key: &str) -> Result<Vec<SqlQueryRevision>, SqlQueryEngineError>
+    {
+        let query = format!(
+            "SELECT item_key, revision, definition, author, comment, created_at, active FROM {} WHERE item_key = $1 ORDER BY revision",
+            self.table_query_revisions
+        );
+
+        sqlx::query_as::<sqlx::Postgres, SqlQueryRevision>(query.as_str())
+            .bind(item_key)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(self.revision_error(item_key))
+    }
+
+    /// Récupère une révision d'une requête.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// * `revision`: Numéro de la révision
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorRevisionNotFound)`: Si la révision n'existe pas
+    pub async fn require_revision(&self, item_key: &str, revision: i32) -> Result<SqlQueryRevision, SqlQueryEngineError>
+    {
+        let query = format!(
+            "SELECT item_key, revision, definition, author, comment, created_at, active FROM {} WHERE item_key = $1 AND revision = $2",
+            self.table_query_revisions
+        );
+
+        sqlx::query_as::<sqlx::Postgres, SqlQueryRevision>(query.as_str())
+            .bind(item_key)
+            .bind(revision)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(self.revision_error(item_key))?
+            .ok_or_else(|| SqlQueryEngineError::ErrorRevisionNotFound { item_key: item_key.to_string(), revision })
+    }
+
+    /// Remet en service une révision antérieure (ou postérieure) : elle devient la révision active
+    /// et sa définition est recopiée dans les tables. L'historique n'est pas modifié.
+    /// # Arguments
+    /// * `item_key`: Clé d'élément unique pour identifier la requête
+    /// * `revision`: Numéro de la révision à remettre en service
+    /// # Returns
+    /// * `Ok(SqlQueryRevision)`: La révision désormais active
+    /// * `Err(SqlQueryEngineError::ErrorRevisionNotFound)`: Si la révision n'existe pas
+    pub async fn rollback(&self, item_key: &str, revision: i32) -> Result<SqlQueryRevision, SqlQueryEngineError>
+    {
+        let mut target = self.require_revision(item_key, revision).await?;
+        let issues = self.check_definition(target.definition());
+        if !issues.is_empty() {
+            return Err(SqlQueryEngineError::ErrorLoadQueryDefinitions { issues });
+        }
+
+        let mut transaction = self.pool.begin().await.map_err(self.revision_error(item_key))?;
+        self.lock_item_key(&mut transaction, item_key).await?;
+        self.apply_definition(&mut transaction, target.definition()).await?;
+        sqlx::query(&format!("UPDATE {} SET active = true WHERE item_key = $1 AND revision = $2", self.table_query_revisions))
+            .bind(item_key)
+            .bind(revision)
+            .execute(&mut *transaction)
+            .await
+            .map_err(self.revision_error(item_key))?;
+        self.deactivate_other_revisions(&mut transaction, item_key, revision).await?;
+
+        transaction.commit().await.map_err(self.revision_error(item_key))?;
+        self.cache.invalidate(item_key);
+
+        target.active = true;
+        Ok(target)
+    }
+
+    async fn deactivate_other_revisions(&self, connection: &mut PgConnection, item_key: &str, revision: i32) -> Result<(), SqlQueryEngineError>
+    {
+        sqlx::query(&format!("UPDATE {} SET active = false WHERE item_key = $1 AND revision <> $2 AND active", self.table_query_revisions))
+            .bind(item_key)
+            .bind(revision)
+            .execute(&mut *connection)
+            .await
+            .map_err(self.revision_error(item_key))?;
+        Ok(())
+    }
+
+    /// Écrit une définition dans les tables des requêtes et des paramètres (création ou mise à jour).
+    async fn apply_definition(&self, connection: &mut PgConnection, definition: &SqlQueryDefinition) -> Result<(), SqlQueryEngineError>
+    {
+        let (query, params) = definition.to_parts();
+        let current = sqlx::query_as::<sqlx::Postgres, SqlQuery>(&format!("SELECT * FROM {} WHERE item_key = $1 FOR UPDATE", self.table_query))
+            .bind(&query.item_key)
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(|source| SqlQueryEngineError::ErrorGetSqlQuery {
+                table: self.table_query.clone(),
+                item_key: query.item_key.clone(),
+                source,
+            })?;
+
+        match current {
+            Some(current) => self.update_sql_query(connection, &current, &query, &params).await,
+            None => self.insert_sql_query(connection, &query, &params).await,
+        }
+    }
+
+    fn revision_error(&self, item_key: &str) -> impl FnOnce(sqlx::Error) -> SqlQueryEngineError
+    {
+        let table = self.table_query_revisions.clone();
+        let item_key = item_key.to_string();
+        move |source| SqlQueryEngineError::ErrorRevision { table, item_key, source }
+    }
+
+    fn register_error(table: &str, item_key: &str) -> impl FnOnce(sqlx::Error) -> SqlQueryEngineError
+    {
+        let table = table.to_string();
+        let item_key = item_key.to_string();
+        move |source| SqlQueryEngineError::ErrorRegisterSqlQuery { table, item_key, source }
+    }
+
+}
+
+impl<DB: SqlBackend> SqlQueryManager<DB> {
+
+    /// Vérifie le statut de la requête, puis que les rôles de l'appelant y donnent accès.
+    fn authorize(&self, dynamic_query: SqlDynamicQuery, roles: &[String]) -> Result<SqlDynamicQuery, SqlQueryEngineError>
+    {
+        let dynamic_query = self.check_status(dynamic_query)?;
+        match dynamic_query.query.is_accessible_by(roles) {
+            true => Ok(dynamic_query),
+            false => Err(SqlQueryEngineError::ErrorAccessDenied { item_key: dynamic_query.query.item_key.clone() }),
+        }
+    }
+
+    /// Vérifie que le statut de la requête permet de l'exécuter à la date courante.
+    fn check_status(&self, dynamic_query: SqlDynamicQuery) -> Result<SqlDynamicQuery, SqlQueryEngineError>
+    {
+        let status = dynamic_query.query.status_at(Utc::now());
+        match status.is_runnable(self.allow_drafts) {
+            true => Ok(dynamic_query),
+            false => Err(SqlQueryEngineError::ErrorQueryUnavailable { item_key: dynamic_query.query.item_key.clone(), status }),
+        }
+    }
+
+    fn no_query_found(item_key: &str) -> SqlQueryEngineError
+    {
+        SqlQueryEngineError::ErrorNoQueryFound { item_key: item_key.to_string() }
+    }
+
+    /// Recherche, valide et exécute une requête dynamique en un seul appel.
+    /// La requête est recherchée (et mise en cache si le cache est actif) par l'item_key
+    /// de `dynamic_query_data`, puis exécutée sur le pool de sa base (voir `pool_for`) avec les limites par défaut.
+    /// Une lecture éligible (voir `is_replica_eligible`) est exécutée sur un réplica, le pool principal servant de repli.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// # Returns
+    /// * `Ok(Vec<T>)`: Les lignes renvoyées par la requête
+    /// * `Err(SqlQueryEngineError::ErrorNoQueryFound)`: Si aucune requête n'a cet item_key
+    /// * `Err(SqlQueryEngineError::ErrorAccessDenied)`: Si la requête est restreinte à des rôles
+    ///   (sans contexte, seules les requêtes sans restriction sont accessibles)
+    /// * `Err(SqlQueryEngineError::ErrorGetSqlQuery | ErrorGetSqlQueryParam)`: Si la recherche échoue
+    /// * `Err(SqlQueryEngineError::ErrorUnsafeSql)`: Si le code SQL est refusé par l'analyse de sécurité
+    /// * `Err(SqlQueryEngineError::ErrorUnknownDatasource)`: Si la requête désigne une base inconnue du gestionnaire
+    /// * `Err(SqlQueryEngineError::ErrorMaskingUnsupported)`: Si des colonnes doivent être masquées
+    ///   pour l'appelant (utiliser `run_json`)
+    /// * `Err(SqlQueryEngineError::ErrorMissingHashKey)`: Si des colonnes doivent être remplacées par leur empreinte
+    ///   pour l'appelant sans clé configurée (voir `SqlQueryManagerBuilder::hash_key`)
+    /// * `Err(e)` avec `e.is_validation_error()`: Si les paramètres sont invalides
+    ///   (`ErrorValidation` avec tous les problèmes si `collect_validation_errors` est actif)
+    /// * `Err(SqlQueryEngineError::ErrorExecutionQuery | ErrorRowLimitExceeded | ErrorTimeout)`: Si l'exécution échoue
+    pub async fn run<T>(&self, dynamic_query_data: SqlDynamicQueryData) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        self.run_with_context::<T>(dynamic_query_data, &ExecutionContext::default()).await
+    }
+
+    /// Identique à `run`, avec un contexte d'exécution fourni par l'appelant
+    /// (utilisé notamment par les valeurs par défaut `${context.<clé>}`).
+    /// L'accès à la requête est vérifié avec les rôles du contexte (`ErrorAccessDenied` sinon).
+    /// Si l'audit est actif, chaque appel est tracé, y compris les refus et les erreurs de validation.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// * `context`: Contexte d'exécution
+    pub async fn run_with_context<T>(&self, dynamic_query_data: SqlDynamicQueryData, context: &ExecutionContext) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        let (result, _) = self.run_rows::<T>(dynamic_query_data, context, false).await?;
+        Ok(result.into_rows())
+    }
+
+    /// Identique à `run_with_context`, avec les métadonnées de l'exécution : les avertissements
+    /// (requête dépréciée...) sont renvoyés avec les lignes, en plus d'être tracés (`tracing`).
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// * `context`: Contexte d'exécution
+    pub async fn run_with_metadata<T>(&self, dynamic_query_data: SqlDynamicQueryData, context: &ExecutionContext) -> Result<SqlQueryResult<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        let (result, _) = self.run_rows::<T>(dynamic_query_data, context, false).await?;
+        Ok(result)
+    }
+
+    /// Exécute la requête et renvoie le résultat avec les politiques de masquage de la requête.
+    /// Si `maskable` est faux (résultat typé), une requête dont des colonnes doivent être masquées
+    /// pour l'appelant est refusée.
+    async fn run_rows<T>(
+        &self,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        maskable: bool,
+    ) -> Result<(SqlQueryResult<T>, Vec<SqlColumnPolicy>), SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        // La définition est lue avant les contrôles d'accès pour masquer les paramètres sensibles
+        // dans la trace d'audit d'un refus ; faute de définition, toutes les valeurs sont masquées.
+        let mut dynamic_query_data = dynamic_query_data;
+        let dynamic_query = match self.get_sql_dynamic_query_any_status(&dynamic_query_data.item_key).await {
+            Ok(Some(dynamic_query)) => {
+                dynamic_query.mark_sensitive_params(&mut dynamic_query_data);
+                self.authorize(dynamic_query, &context.roles)
+            },
+            Ok(None) => {
+                dynamic_query_data.mark_all_sensitive();
+                Err(Self::no_query_found(&dynamic_query_data.item_key))
+            },
+            Err(error) => {
+                dynamic_query_data.mark_all_sensitive();
+                Err(error)
+            },
+        };
+        self.run_resolved_rows(dynamic_query, dynamic_query_data, context, maskable).await
+    }
+
+    /// Identique à `run_rows`, pour une requête déjà recherchée (ou l'erreur de sa recherche, tracée par l'audit).
+    /// Les paramètres sensibles de `dynamic_query_data` doivent déjà être déclarés.
+    async fn run_resolved_rows<T>(
+        &self,
+        dynamic_query: Result<SqlDynamicQuery, SqlQueryEngineError>,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        maskable: bool,
+    ) -> Result<(SqlQueryResult<T>, Vec<SqlColumnPolicy>), SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        let (column_policies, warnings) = match &dynamic_query {
+            Ok(dynamic_query) => (dynamic_query.query.column_policies().to_vec(), dynamic_query.query.warnings_at(Utc::now())),
+            Err(_) => (Vec::new(), Vec::new()),
+        };
+
+        let rows = match &self.auditor {
+            Some(auditor) => {
+                let audited_data = dynamic_query_data.clone();
+                auditor.audit(&audited_data, context, self.run_query(dynamic_query, dynamic_query_data, context, maskable)).await
+            },
+            None => self.run_query(dynamic_query, dynamic_query_data, context, maskable).await,
+        }?;
+
+        for warning in &warnings {
+            tracing::warn!(item_key = %warning.item_key(), user_id = ?context.user_id, "{}", warning);
+        }
+
+        Ok((SqlQueryResult { rows, warnings }, column_policies))
+    }
+
+    async fn run_query<T>(
+        &self,
+        dynamic_query: Result<SqlDynamicQuery, SqlQueryEngineError>,
+        dynamic_query_data: SqlDynamicQueryData,
+        context: &ExecutionContext,
+        maskable: bool,
+    ) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, DB::Row> + Send + Unpin,
+    {
+        let dynamic_query = dynamic_query?;
+        let kind = dynamic_query.query.check_safety(self.max_statement_kind, DB::sql_dialect())?;
+        let pool = self.pool_for(&dynamic_query.query)?;
+
+        if !maskable && dynamic_query.query.is_masked_for(&context.roles) {
+            return Err(SqlQueryEngineError::ErrorMaskingUnsupported { item_key: dynamic_query.query.item_key.clone() });
+        }
+        if self.hash_key.is_none() && dynamic_query.query.is_hashed_for(&context.roles) {
+            return Err(SqlQueryEngineError::ErrorMissingHashKey { item_key: dynamic_query.query.item_key.clone() });
+        }
+
+        if self.collect_validation_errors {
+            dynamic_query.validate_query_params(&dynamic_query_data, context).into_result()?;
+        }
+
+        let limits = self.limits.with_max_statement_kind(self.max_statement_kind);
+
+        // Le masquage est vérifié ci-dessus : un résultat JSON est masqué ensuite par `json_result`
+        // Lecture seule : réplicas à tour de rôle, puis pool principal si aucun ne répond
+        if self.is_replica_eligible(&dynamic_query.query, kind) {
+            for (index, replica) in self.replicas.candidates() {
+                match dynamic_query.fetch_on::<T, DB>(&replica, dynamic_query_data.clone(), context, limits).await {
+                    Err(error) if error.is_connection_error() => {
+                        tracing::warn!(item_key = %dynamic_query.query.item_key, replica = index, "Replica unavailable: {}", error);
+                        self.replicas.mark_unavailable(index);
+                    },
+                    result => return result,
+                }
+            }
+        }
+
+        dynamic_query.fetch_on::<T, DB>(pool, dynamic_query_data, context, limits).await
+    }
+
+    /// Identique à `run`, mais renvoie chaque ligne sous forme d'objet JSON
+    /// (voir `SqlJsonRow` pour les conversions de types), après application des politiques
+    /// de masquage des colonnes de la requête (voir `SqlColumnPolicy`).
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    pub async fn run_json(&self, dynamic_query_data: SqlDynamicQueryData) -> Result<Vec<serde_json::Value>, SqlQueryEngineError>
+    where
+        SqlJsonRow: for<'r> sqlx::FromRow<'r, DB::Row>,
+    {
+        self.run_json_with_context(dynamic_query_data, &ExecutionContext::default()).await
     }
 
-}
\ No newline at end of file
+    /// Identique à `run_json`, avec un contexte d'exécution fourni par l'appelant.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// * `context`: Contexte d'exécution
+    pub async fn run_json_with_context(&self, dynamic_query_data: SqlDynamicQueryData, context: &ExecutionContext) -> Result<Vec<serde_json::Value>, SqlQueryEngineError>
+    where
+        SqlJsonRow: for<'r> sqlx::FromRow<'r, DB::Row>,
+    {
+        Ok(self.run_json_with_metadata(dynamic_query_data, context).await?.into_rows())
+    }
+
+    /// Identique à `run_json_with_context`, avec les métadonnées de l'exécution (voir `run_with_metadata`).
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// * `context`: Contexte d'exécution
+    pub async fn run_json_with_metadata(&self, dynamic_query_data: SqlDynamicQueryData, context: &ExecutionContext) -> Result<SqlQueryResult<serde_json::Value>, SqlQueryEngineError>
+    where
+        SqlJsonRow: for<'r> sqlx::FromRow<'r, DB::Row>,
+    {
+        let (result, column_policies) = self.run_rows::<SqlJsonRow>(dynamic_query_data, context, true).await?;
+        Ok(self.json_result(result, &column_policies, context))
+    }
+
+    /// Convertit les lignes en objets JSON et applique les politiques de masquage.
+    fn json_result(&self, result: SqlQueryResult<SqlJsonRow>, column_policies: &[SqlColumnPolicy], context: &ExecutionContext) -> SqlQueryResult<serde_json::Value>
+    {
+        let mut rows: Vec<serde_json::Value> = result.rows.into_iter().map(SqlJsonRow::into_inner).collect();
+        apply_column_policies(column_policies, &context.roles, self.hash_key.as_ref(), &mut rows);
+        SqlQueryResult { rows, warnings: result.warnings }
+    }
+
+}
+
+impl SqlQueryManager {
+
+    /// Exécute une révision précise d'une requête (par exemple pour comparer ses résultats avant un `rollback`),
+    /// avec les mêmes contrôles que `run_with_context` : rôles et statut de la révision, analyse de sécurité, audit.
+    /// # Arguments
+    /// * `dynamic_query_data`: Item_key et paramètres de la requête
+    /// * `revision`: Numéro de la révision à exécuter
+    /// * `context`: Contexte d'exécution
+    /// # Returns
+    /// * `Err(SqlQueryEngineError::ErrorRevisionNotFound)`: Si la révision n'existe pas
+    pub async fn run_revision<T>(&self, dynamic_query_data: SqlDynamicQueryData, revision: i32, context: &ExecutionContext) -> Result<Vec<T>, SqlQueryEngineError>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+    {
+        let mut dynamic_query_data = dynamic_query_data;
+        let dynamic_query = self.require_authorized_revision(&mut dynamic_query_data, revision, &context.roles).await;
+        let (result, _) = self.run_resolved_rows::<T>(dynamic_query, dynamic_query_data, context, false).await?;
+        Ok(result.into_rows())
+    }
+
+    /// Identique à `run_revision`, avec un résultat JSON masqué selon les politiques de la révision.
+    pub async fn run_json_revision(&self, dynamic_query_data: SqlDynamicQueryData, revision: i32, context: &ExecutionContext) -> Result<Vec<serde_json::Value>, SqlQueryEngineError>
+    {
+        let mut dynamic_query_data = dynamic_query_data;
+        let dynamic_query = self.require_authorized_revision(&mut dynamic_query_data, revision, context.roles.as_slice()).await;
+        let (result, column_policies) = self.run_resolved_rows::<SqlJsonRow>(dynamic_query, dynamic_query_data, context, true).await?;
+        Ok(self.json_result(result, &column_policies, context).into_rows())
+    }
+
+    /// Recherche une révision et vérifie son accès, après avoir déclaré ses paramètres sensibles dans
+    /// `dynamic_query_data` (tous si la révision ne peut pas être lue).
+    async fn require_authorized_revision(&self, dynamic_query_data: &mut SqlDynamicQueryData, revision: i32, roles: &[String]) -> Result<SqlDynamicQuery, SqlQueryEngineError>
+    {
+        let item_key = dynamic_query_data.item_key.clone();
+        let dynamic_query = match self.require_revision(&item_key, revision).await {
+            Ok(target) => target.dynamic_query(),
+            Err(error) => {
+                dynamic_query_data.mark_all_sensitive();
+                return Err(error);
+            },
+        };
+        dynamic_query.mark_sensitive_params(dynamic_query_data);
+        match dynamic_query.query.is_accessible_by(roles) {
+            true => self.check_status(dynamic_query),
+            false => Err(SqlQueryEngineError::ErrorAccessDenied { item_key }),
+        }
+    }
+
+}
+
+type PgQuery<'q> = sqlx::query::Query<'q, Postgres, sqlx::postgres::PgArguments>;
+
+/// Colonne optionnelle de la table des requêtes, écrite par `insert_sql_query` et `update_sql_query`.
+/// # Champs
+/// - `name`: Nom de la colonne
+/// - `is_set`: Indique si la requête renseigne la colonne
+/// - `bind`: Lie la valeur de la colonne (`NULL` si elle n'est pas renseignée)
+struct OptionalQueryColumn
+{
+    name: &'static str,
+    is_set: fn(&SqlQuery) -> bool,
+    bind: for<'q> fn(PgQuery<'q>, &'q SqlQuery) -> PgQuery<'q>,
+}
+
+/// Colonnes optionnelles de la table des requêtes, dans l'ordre où elles sont écrites.
+const OPTIONAL_QUERY_COLUMNS: [OptionalQueryColumn; 9] = [
+    OptionalQueryColumn { name: "rules", is_set: |query| query.rules.is_some(), bind: |statement, query| statement.bind(&query.rules) },
+    OptionalQueryColumn { name: "allowed_roles", is_set: |query| query.allowed_roles.is_some(), bind: |statement, query| statement.bind(&query.allowed_roles) },
+    OptionalQueryColumn { name: "statement_kind", is_set: |query| query.statement_kind.is_some(), bind: |statement, query| statement.bind(&query.statement_kind) },
+    OptionalQueryColumn { name: "read_only", is_set: |query| query.read_only.is_some(), bind: |statement, query| statement.bind(query.read_only) },
+    OptionalQueryColumn { name: "column_policies", is_set: |query| query.column_policies.is_some(), bind: |statement, query| statement.bind(&query.column_policies) },
+    OptionalQueryColumn { name: "status", is_set: |query| query.status.is_some(), bind: |statement, query| statement.bind(&query.status) },
+    OptionalQueryColumn { name: "sunset_at", is_set: |query| query.sunset_at.is_some(), bind: |statement, query| statement.bind(query.sunset_at) },
+    OptionalQueryColumn { name: "datasource", is_set: |query| query.datasource.is_some(), bind: |statement, query| statement.bind(&query.datasource) },
+    OptionalQueryColumn { name: "needs_fresh_data", is_set: |query| query.needs_fresh_data.is_some(), bind: |statement, query| statement.bind(query.needs_fresh_data) },
+];
+
+/// Liste de paramètres positionnels `$1, $2, ...`
+fn placeholders(count: usize) -> String {
+    (1..=count).map(|i| format!("${}", i)).collect::<Vec<_>>().join(", ")
+}
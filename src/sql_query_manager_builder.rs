@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::{Pool, Postgres};
+use crate::sql_audit::SqlAuditor;
+use crate::sql_backend::SqlBackend;
+use crate::sql_column_policy::SqlHashKey;
+use crate::sql_query_cache::SqlQueryCache;
+use crate::sql_query_limits::SqlQueryLimits;
+use crate::sql_query_manager::SqlQueryManager;
+use crate::sql_replica_set::SqlReplicaSet;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_safety::SqlStatementKind;
+
+/// Constructeur de `SqlQueryManager`.
+/// Permet de configurer les noms des tables, le schéma, le cache et les limites par défaut.
+/// # Valeurs par défaut
+/// - `table_query`: `sql_query`
+/// - `table_query_params`: `sql_query_param`
+/// - `table_query_revisions`: `sql_query_revision`
+/// - `schema`: aucun
+/// - cache désactivé, capacité `SqlQueryCache::DEFAULT_CAPACITY`
+/// - aucune limite
+/// - validation arrêtée au premier problème
+/// - code SQL limité aux lectures et aux modifications de données (`SqlStatementKind::Dml`)
+/// - brouillons (`SqlQueryStatus::Draft`) refusés
+/// - aucun audit
+/// - aucune clé d'empreinte : les requêtes ayant une politique `hash` ne peuvent pas être exécutées
+/// - aucune base nommée : toutes les requêtes sont exécutées sur `pool`
+/// - aucun réplica, réplica injoignable écarté pendant `SqlReplicaSet::DEFAULT_RETRY_AFTER`
+/// - définitions lues dans les tables `table_query` et `table_query_params`
+///   (`PgQueryRepository` ou `SqliteQueryRepository` selon le backend)
+pub struct SqlQueryManagerBuilder<DB: SqlBackend = Postgres>
+{
+    pub(crate) pool: Pool<DB>,
+    pub(crate) table_query: String,
+    pub(crate) table_query_params: String,
+    pub(crate) table_query_revisions: String,
+    pub(crate) schema: Option<String>,
+    pub(crate) cache_ttl: Option<Duration>,
+    pub(crate) cache_capacity: usize,
+    pub(crate) limits: SqlQueryLimits,
+    pub(crate) collect_validation_errors: bool,
+    pub(crate) max_statement_kind: SqlStatementKind,
+    pub(crate) allow_drafts: bool,
+    pub(crate) auditor: Option<SqlAuditor>,
+    pub(crate) hash_key: Option<SqlHashKey>,
+    pub(crate) repository: Option<Arc<dyn QueryRepository>>,
+    pub(crate) datasources: HashMap<String, Pool<DB>>,
+    pub(crate) replicas: Vec<Pool<DB>>,
+    pub(crate) replica_retry_after: Duration,
+}
+
+impl<DB: SqlBackend> Clone for SqlQueryManagerBuilder<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table_query: self.table_query.clone(),
+            table_query_params: self.table_query_params.clone(),
+            table_query_revisions: self.table_query_revisions.clone(),
+            schema: self.schema.clone(),
+            cache_ttl: self.cache_ttl,
+            cache_capacity: self.cache_capacity,
+            limits: self.limits,
+            collect_validation_errors: self.collect_validation_errors,
+            max_statement_kind: self.max_statement_kind,
+            allow_drafts: self.allow_drafts,
+            auditor: self.auditor.clone(),
+            hash_key: self.hash_key.clone(),
+            repository: self.repository.clone(),
+            datasources: self.datasources.clone(),
+            replicas: self.replicas.clone(),
+            replica_retry_after: self.replica_retry_after,
+        }
+    }
+}
+
+impl<DB: SqlBackend> std::fmt::Debug for SqlQueryManagerBuilder<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlQueryManagerBuilder")
+            .field("pool", &self.pool)
+            .field("table_query", &self.table_query)
+            .field("table_query_params", &self.table_query_params)
+            .field("table_query_revisions", &self.table_query_revisions)
+            .field("schema", &self.schema)
+            .field("cache_ttl", &self.cache_ttl)
+            .field("cache_capacity", &self.cache_capacity)
+            .field("limits", &self.limits)
+            .field("collect_validation_errors", &self.collect_validation_errors)
+            .field("max_statement_kind", &self.max_statement_kind)
+            .field("allow_drafts", &self.allow_drafts)
+            .field("auditor", &self.auditor)
+            .field("hash_key", &self.hash_key)
+            .field("repository", &self.repository)
+            .field("datasources", &self.datasources)
+            .field("replicas", &self.replicas)
+            .field("replica_retry_after", &self.replica_retry_after)
+            .finish()
+    }
+}
+
+impl SqlQueryManagerBuilder {
+    /// Nom par défaut de la table contenant les requêtes
+    pub const DEFAULT_TABLE_QUERY: &'static str = "sql_query";
+
+    /// Nom par défaut de la table contenant les paramètres de requête
+    pub const DEFAULT_TABLE_QUERY_PARAMS: &'static str = "sql_query_param";
+
+    /// Nom par défaut de la table contenant les révisions des requêtes
+    pub const DEFAULT_TABLE_QUERY_REVISIONS: &'static str = "sql_query_revision";
+}
+
+impl<DB: SqlBackend> SqlQueryManagerBuilder<DB> {
+    /// Crée un nouveau constructeur.
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base de données
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self {
+            pool,
+            table_query: SqlQueryManagerBuilder::DEFAULT_TABLE_QUERY.to_string(),
+            table_query_params: SqlQueryManagerBuilder::DEFAULT_TABLE_QUERY_PARAMS.to_string(),
+            table_query_revisions: SqlQueryManagerBuilder::DEFAULT_TABLE_QUERY_REVISIONS.to_string(),
+            schema: None,
+            cache_ttl: None,
+            cache_capacity: SqlQueryCache::DEFAULT_CAPACITY,
+            limits: SqlQueryLimits::default(),
+            collect_validation_errors: false,
+            max_statement_kind: SqlStatementKind::Dml,
+            allow_drafts: false,
+            auditor: None,
+            hash_key: None,
+            repository: None,
+            datasources: HashMap::new(),
+            replicas: Vec::new(),
+            replica_retry_after: SqlReplicaSet::<DB>::DEFAULT_RETRY_AFTER,
+        }
+    }
+
+    /// Nom de la table SQL contenant les requêtes
+    pub fn table_query(mut self, table_query: impl Into<String>) -> Self {
+        self.table_query = table_query.into();
+        self
+    }
+
+    /// Nom de la table SQL contenant les paramètres de requête
+    pub fn table_query_params(mut self, table_query_params: impl Into<String>) -> Self {
+        self.table_query_params = table_query_params.into();
+        self
+    }
+
+    /// Nom de la table SQL contenant les révisions des requêtes (voir `SqlQueryManager::save_revision`)
+    pub fn table_query_revisions(mut self, table_query_revisions: impl Into<String>) -> Self {
+        self.table_query_revisions = table_query_revisions.into();
+        self
+    }
+
+    /// Schéma préfixé aux noms des tables (ex : `data_analyst`)
+    pub fn schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Active le cache des requêtes dynamiques avec la durée de vie donnée
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Nombre maximal de requêtes conservées dans le cache
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Nombre maximal de lignes acceptées par défaut en retour d'une exécution
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.limits.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Durée maximale d'exécution par défaut d'une requête
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.limits.timeout = Some(timeout);
+        self
+    }
+
+    /// Si vrai, toutes les requêtes sont exécutées dans une transaction `START TRANSACTION READ ONLY` :
+    /// PostgreSQL refuse alors toute écriture, y compris par une fonction appelée par la requête
+    /// (sur SQLite, la connexion est passée en `PRAGMA query_only`)
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.limits.read_only = read_only;
+        self
+    }
+
+    /// Limites par défaut appliquées lors de l'exécution
+    pub fn limits(mut self, limits: SqlQueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Si vrai, `run` et `run_json` vérifient tous les paramètres et renvoient
+    /// une `SqlQueryEngineError::ErrorValidation` contenant le rapport complet,
+    /// au lieu de s'arrêter au premier problème.
+    pub fn collect_validation_errors(mut self, collect: bool) -> Self {
+        self.collect_validation_errors = collect;
+        self
+    }
+
+    /// Nature la plus risquée autorisée pour le code SQL des requêtes,
+    /// vérifiée à l'enregistrement et avant chaque exécution
+    /// (ex : `SqlStatementKind::ReadOnly` pour n'autoriser que les lectures).
+    pub fn max_statement_kind(mut self, kind: SqlStatementKind) -> Self {
+        self.max_statement_kind = kind;
+        self
+    }
+
+    /// Si vrai, les requêtes au statut `draft` sont exécutables (par exemple en recette).
+    /// Les requêtes désactivées restent refusées.
+    pub fn allow_drafts(mut self, allow_drafts: bool) -> Self {
+        self.allow_drafts = allow_drafts;
+        self
+    }
+
+    /// Trace chaque appel de `run` / `run_json` (voir `SqlAuditor` et `SqlAuditPgSink`)
+    pub fn audit(mut self, auditor: SqlAuditor) -> Self {
+        self.auditor = Some(auditor);
+        self
+    }
+
+    /// Clé secrète des empreintes HMAC-SHA256 des colonnes masquées par une politique `hash`
+    /// (voir `SqlColumnAction::Hash`). Les empreintes restent stables tant que la clé ne change pas.
+    pub fn hash_key(mut self, hash_key: SqlHashKey) -> Self {
+        self.hash_key = Some(hash_key);
+        self
+    }
+
+    /// Source des définitions de requêtes (fichiers, mémoire, combinaison...) à la place des tables.
+    /// Les requêtes sont toujours exécutées sur le pool du gestionnaire.
+    pub fn repository(mut self, repository: impl QueryRepository + 'static) -> Self {
+        self.repository = Some(Arc::new(repository));
+        self
+    }
+
+    /// Identique à `repository`, avec un dépôt partagé.
+    pub fn shared_repository(mut self, repository: Arc<dyn QueryRepository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Déclare une base nommée : les requêtes dont la colonne `datasource` vaut `name`
+    /// sont exécutées sur ce pool (les autres sur le pool principal).
+    /// Le registre des requêtes reste lu sur le pool principal.
+    pub fn datasource(mut self, name: impl Into<String>, pool: Pool<DB>) -> Self {
+        self.datasources.insert(name.into(), pool);
+        self
+    }
+
+    /// Ajoute un réplica en lecture du pool principal. Les requêtes en lecture seule
+    /// (analyse du code SQL, voir `SqlStatementKind::ReadOnly`) sont exécutées à tour de rôle
+    /// sur un réplica en bonne santé ; les écritures, les requêtes `needs_fresh_data` et les requêtes
+    /// d'une base nommée restent sur leur pool. Sans réplica disponible, le pool principal est utilisé.
+    pub fn replica(mut self, pool: Pool<DB>) -> Self {
+        self.replicas.push(pool);
+        self
+    }
+
+    /// Durée pendant laquelle un réplica injoignable n'est plus utilisé
+    pub fn replica_retry_after(mut self, retry_after: Duration) -> Self {
+        self.replica_retry_after = retry_after;
+        self
+    }
+
+    /// Construit le `SqlQueryManager`.
+    pub fn build(self) -> SqlQueryManager<DB> {
+        SqlQueryManager::from_builder(self)
+    }
+}
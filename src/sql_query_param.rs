@@ -1,4 +1,6 @@
 use sqlx::prelude::FromRow;
+use sqlx::types::Json;
+use crate::sql_param_constraints::SqlParamConstraints;
 
 #[derive(Debug, Clone, FromRow)]
 pub struct SqlQueryParam {
@@ -10,4 +12,29 @@ pub struct SqlQueryParam {
    pub default_value: Option<String>,
    pub description: Option<String>,
    pub item_key: String,
-}
\ No newline at end of file
+   /// Contraintes sur la valeur (colonne JSONB `constraints`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub constraints: Option<Json<SqlParamConstraints>>,
+   /// Si vrai, la valeur est masquée dans les erreurs, les traces d'audit et le `Debug`
+   /// de `SqlDynamicQueryData` (colonne `BOOLEAN` `sensitive`, optionnelle dans la table)
+   #[sqlx(default)]
+   pub sensitive: Option<bool>,
+}
+
+impl SqlQueryParam {
+    /// Valeur remplaçant celle d'un paramètre sensible
+    pub const REDACTED: &'static str = "***";
+
+    /// Indique si la valeur du paramètre est sensible (email, numéro de sécurité sociale...).
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive.unwrap_or(false)
+    }
+
+    /// Valeur à afficher dans un message : la valeur elle-même, ou `***` si le paramètre est sensible.
+    pub fn display_value(&self, value: &str) -> String {
+        match self.is_sensitive() {
+            true => Self::REDACTED.to_string(),
+            false => value.to_string(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 #[derive(Debug, Clone)]
 pub enum SqlQueryParamType {
     String,
@@ -8,17 +10,29 @@ pub enum SqlQueryParamType {
     NaiveDateTime,
 }
 
-impl From<String> for SqlQueryParamType {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "VARCHAR" | "Varchar" => SqlQueryParamType::String,
-            "BIGINT" | "INTEGER" | "Integer" => SqlQueryParamType::I32,
-            "DOUBLE PRECISION" | "DOUBLE_PRECISION" => SqlQueryParamType::F64,
-            "BOOLEAN" | "Boolean" => SqlQueryParamType::Bool,
-            "DATE" | "Date" => SqlQueryParamType::NaiveDate,
-            "DATETIME" | "DateTime" => SqlQueryParamType::NaiveDateTime,
-            
-            _ => panic!("Unknown SQL query parameter type: {}", value),
+impl FromStr for SqlQueryParamType {
+    type Err = String;
+
+    /// Convertit le type déclaré dans la table des paramètres.
+    /// Renvoie le type inconnu en erreur.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "VARCHAR" | "Varchar" => Ok(SqlQueryParamType::String),
+            "BIGINT" | "INTEGER" | "Integer" => Ok(SqlQueryParamType::I32),
+            "DOUBLE PRECISION" | "DOUBLE_PRECISION" => Ok(SqlQueryParamType::F64),
+            "BOOLEAN" | "Boolean" => Ok(SqlQueryParamType::Bool),
+            "DATE" | "Date" => Ok(SqlQueryParamType::NaiveDate),
+            "DATETIME" | "DateTime" => Ok(SqlQueryParamType::NaiveDateTime),
+
+            _ => Err(value.to_string()),
         }
     }
-}
\ No newline at end of file
+}
+
+impl TryFrom<String> for SqlQueryParamType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
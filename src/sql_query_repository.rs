@@ -0,0 +1,38 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_definition::SqlQueryDefinition;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+
+/// Source des définitions de requêtes (requêtes et paramètres).
+///
+/// `SqlQueryManager` lit les définitions via ce trait et exécute toujours les requêtes sur son pool :
+/// les définitions peuvent venir des tables PostgreSQL (`PgQueryRepository`) ou SQLite (`SqliteQueryRepository`), d'une map en mémoire
+/// (`MemoryQueryRepository`), de fichiers JSON/YAML (`FileQueryRepository`) ou d'une combinaison
+/// (`LayeredQueryRepository`).
+#[async_trait]
+pub trait QueryRepository: fmt::Debug + Send + Sync
+{
+    /// Récupère une requête par son item_key, `None` si elle n'existe pas.
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>;
+
+    /// Récupère les paramètres d'une requête triés par `param_order`.
+    /// Renvoie `Some(vec![])` pour une requête sans paramètre et `None` si la requête n'existe pas.
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>;
+
+    /// Liste toutes les requêtes, triées par item_key.
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>;
+
+    /// Définitions complètes (requête et paramètres) de toutes les requêtes, triées par item_key.
+    async fn definitions(&self) -> Result<Vec<SqlQueryDefinition>, SqlQueryEngineError>
+    {
+        let mut definitions = Vec::new();
+        for query in self.list().await? {
+            let params = self.get_params(&query.item_key).await?.unwrap_or_default();
+            definitions.push(SqlQueryDefinition::from_parts(&query, &params));
+        }
+        Ok(definitions)
+    }
+}
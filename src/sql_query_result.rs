@@ -0,0 +1,56 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Avertissement produit par l'exécution d'une requête, renvoyé avec le résultat (voir `SqlQueryResult`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "warning", rename_all = "snake_case")]
+pub enum SqlQueryWarning
+{
+    /// La requête est dépréciée ; `sunset_at` est la date à partir de laquelle elle sera désactivée
+    Deprecated { item_key: String, sunset_at: Option<DateTime<Utc>> },
+}
+
+impl SqlQueryWarning {
+    /// Item_key de la requête concernée.
+    pub fn item_key(&self) -> &str {
+        match self {
+            SqlQueryWarning::Deprecated { item_key, .. } => item_key,
+        }
+    }
+}
+
+impl fmt::Display for SqlQueryWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlQueryWarning::Deprecated { item_key, sunset_at: Some(sunset_at) } =>
+                write!(f, "Query '{}' is deprecated and will be disabled on {}", item_key, sunset_at.to_rfc3339()),
+            SqlQueryWarning::Deprecated { item_key, sunset_at: None } =>
+                write!(f, "Query '{}' is deprecated", item_key),
+        }
+    }
+}
+
+/// Résultat d'une exécution accompagné de ses métadonnées (voir `SqlQueryManager::run_with_metadata`).
+/// # Champs
+/// - `rows`: Les lignes renvoyées par la requête
+/// - `warnings`: Les avertissements de l'exécution (requête dépréciée...)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlQueryResult<T>
+{
+    pub rows: Vec<T>,
+    pub warnings: Vec<SqlQueryWarning>,
+}
+
+impl<T> SqlQueryResult<T> {
+    /// Indique si l'exécution a produit des avertissements.
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// Retourne les lignes, sans les métadonnées.
+    pub fn into_rows(self) -> Vec<T> {
+        self.rows
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use sqlx::types::Json;
+use crate::sql_dynamic_query::SqlDynamicQuery;
+use crate::sql_query_definition::SqlQueryDefinition;
+
+/// Révision immuable de la définition d'une requête (voir `SqlQueryManager::save_revision`).
+/// La définition de la révision active est recopiée dans les tables des requêtes et des paramètres.
+/// # Champs
+/// - `item_key`: Clé de la requête
+/// - `revision`: Numéro de révision, à partir de 1 pour chaque item_key
+/// - `definition`: Définition complète (requête et paramètres)
+/// - `author`: Auteur de la modification
+/// - `comment`: Commentaire de la modification (optionnel)
+/// - `created_at`: Date de création de la révision
+/// - `active`: Vrai pour la révision en service (une seule par item_key)
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct SqlQueryRevision
+{
+    pub item_key: String,
+    pub revision: i32,
+    pub definition: Json<SqlQueryDefinition>,
+    pub author: String,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub active: bool,
+}
+
+impl SqlQueryRevision {
+    /// Retourne la définition de la révision.
+    pub fn definition(&self) -> &SqlQueryDefinition {
+        &self.definition
+    }
+
+    /// Construit la requête dynamique de cette révision, exécutable indépendamment de la révision active.
+    pub fn dynamic_query(&self) -> SqlDynamicQuery {
+        let (query, params) = self.definition.to_parts();
+        SqlDynamicQuery {
+            query,
+            params: (!params.is_empty()).then_some(params),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono;
+use crate::sql_query_param_type::SqlQueryParamType;
+
+/// Règle de validation portant sur plusieurs paramètres d'une requête,
+/// stockée en JSON (liste de règles) dans la colonne `rules` de la table des requêtes.
+/// # Exemple
+/// ```json
+/// [
+///   { "rule": "ordering", "first": "date_from", "second": "date_to" },
+///   { "rule": "max_span", "from": "date_from", "to": "date_to", "max_days": 366 },
+///   { "rule": "at_least_one_of", "params": ["nom", "prenom"] },
+///   { "rule": "mutually_exclusive", "params": ["participant_id", "atelier_id"] },
+///   { "rule": "required_if", "param": "motif", "if_param": "statut", "equals": "refuse" }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SqlQueryRule
+{
+    /// `first` doit être inférieur ou égal à `second` (strictement inférieur si `strict`)
+    Ordering {
+        first: String,
+        second: String,
+        #[serde(default)]
+        strict: bool,
+    },
+
+    /// Au moins un des paramètres doit être fourni
+    AtLeastOneOf { params: Vec<String> },
+
+    /// Au plus un des paramètres peut être fourni
+    MutuallyExclusive { params: Vec<String> },
+
+    /// `param` est requis si `if_param` est fourni (et vaut `equals`, si précisé)
+    RequiredIf {
+        param: String,
+        if_param: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        equals: Option<String>,
+    },
+
+    /// L'écart entre les dates `from` et `to` ne doit pas dépasser `max_days` jours
+    MaxSpan { from: String, to: String, max_days: i64 },
+}
+
+/// Valeur typée d'un paramètre, utilisée pour comparer deux paramètres entre eux.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlTypedValue
+{
+    Number(f64),
+    Bool(bool),
+    Date(chrono::NaiveDate),
+    DateTime(chrono::NaiveDateTime),
+    Text(String),
+}
+
+impl SqlTypedValue {
+    /// Interprète une valeur selon le type déclaré du paramètre.
+    /// Renvoie `None` si la valeur ne correspond pas au type.
+    pub fn parse(param_type: &SqlQueryParamType, value: &str) -> Option<Self> {
+        match param_type {
+            SqlQueryParamType::String => Some(SqlTypedValue::Text(value.to_string())),
+            SqlQueryParamType::I32 => value.parse::<i32>().ok().map(|v| SqlTypedValue::Number(v as f64)),
+            SqlQueryParamType::F64 => value.parse::<f64>().ok().map(SqlTypedValue::Number),
+            SqlQueryParamType::Bool => match value.to_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Some(SqlTypedValue::Bool(true)),
+                "false" | "0" | "no" | "off" => Some(SqlTypedValue::Bool(false)),
+                _ => None,
+            },
+            SqlQueryParamType::NaiveDate => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(SqlTypedValue::Date),
+            SqlQueryParamType::NaiveDateTime => chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok().map(SqlTypedValue::DateTime),
+        }
+    }
+
+    /// Compare deux valeurs du même type, `None` si les types diffèrent.
+    pub fn compare(&self, other: &SqlTypedValue) -> Option<Ordering> {
+        match (self, other) {
+            (SqlTypedValue::Number(a), SqlTypedValue::Number(b)) => a.partial_cmp(b),
+            (SqlTypedValue::Bool(a), SqlTypedValue::Bool(b)) => Some(a.cmp(b)),
+            (SqlTypedValue::Date(a), SqlTypedValue::Date(b)) => Some(a.cmp(b)),
+            (SqlTypedValue::DateTime(a), SqlTypedValue::DateTime(b)) => Some(a.cmp(b)),
+            (SqlTypedValue::Text(a), SqlTypedValue::Text(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Date et heure de la valeur, pour les dates et dates/heures.
+    fn as_datetime(&self) -> Option<chrono::NaiveDateTime> {
+        match self {
+            SqlTypedValue::Date(d) => d.and_hms_opt(0, 0, 0),
+            SqlTypedValue::DateTime(dt) => Some(*dt),
+            _ => None,
+        }
+    }
+}
+
+/// Accès aux valeurs fournies pour l'évaluation d'une règle.
+pub trait SqlRuleValues {
+    /// Indique si le paramètre a été fourni par l'appelant.
+    fn is_provided(&self, param_name: &str) -> bool;
+
+    /// Valeur brute fournie pour le paramètre.
+    fn raw(&self, param_name: &str) -> Option<&str>;
+
+    /// Valeur typée du paramètre (fournie ou par défaut), `None` si absente ou invalide.
+    fn typed(&self, param_name: &str) -> Option<SqlTypedValue>;
+}
+
+impl SqlQueryRule {
+    /// Paramètre auquel rattacher une violation de la règle (champ du formulaire à signaler).
+    pub fn target_param(&self) -> &str {
+        match self {
+            SqlQueryRule::Ordering { second, .. } => second,
+            SqlQueryRule::AtLeastOneOf { params } | SqlQueryRule::MutuallyExclusive { params } =>
+                params.first().map(String::as_str).unwrap_or_default(),
+            SqlQueryRule::RequiredIf { param, .. } => param,
+            SqlQueryRule::MaxSpan { to, .. } => to,
+        }
+    }
+
+    /// Évalue la règle.
+    ///
+    /// # Returns
+    /// * `true` - Si la règle est respectée ou ne peut pas être évaluée
+    ///   (valeur absente ou invalide, déjà signalée par la validation des paramètres)
+    pub fn is_satisfied(&self, values: &impl SqlRuleValues) -> bool {
+        match self {
+            SqlQueryRule::Ordering { first, second, strict } => {
+                match (values.typed(first), values.typed(second)) {
+                    (Some(a), Some(b)) => match a.compare(&b) {
+                        Some(Ordering::Less) => true,
+                        Some(Ordering::Equal) => !strict,
+                        Some(Ordering::Greater) => false,
+                        // Types incomparables : règle mal déclarée, ignorée
+                        None => true,
+                    },
+                    _ => true,
+                }
+            },
+            SqlQueryRule::AtLeastOneOf { params } => params.iter().any(|p| values.is_provided(p)),
+            SqlQueryRule::MutuallyExclusive { params } => params.iter().filter(|p| values.is_provided(p)).count() <= 1,
+            SqlQueryRule::RequiredIf { param, if_param, equals } => {
+                let triggered = match (values.raw(if_param), equals) {
+                    (Some(value), Some(expected)) => value == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                !triggered || values.is_provided(param)
+            },
+            SqlQueryRule::MaxSpan { from, to, max_days } => {
+                let from = values.typed(from).and_then(|v| v.as_datetime());
+                let to = values.typed(to).and_then(|v| v.as_datetime());
+                match (from, to) {
+                    (Some(from), Some(to)) => to.signed_duration_since(from).num_seconds() <= max_days.saturating_mul(86_400),
+                    _ => true,
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for SqlQueryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlQueryRule::Ordering { first, second, strict: false } => write!(f, "'{}' must be less than or equal to '{}'", first, second),
+            SqlQueryRule::Ordering { first, second, strict: true } => write!(f, "'{}' must be less than '{}'", first, second),
+            SqlQueryRule::AtLeastOneOf { params } => write!(f, "at least one of {} is required", params.join(", ")),
+            SqlQueryRule::MutuallyExclusive { params } => write!(f, "only one of {} can be provided", params.join(", ")),
+            SqlQueryRule::RequiredIf { param, if_param, equals: None } => write!(f, "'{}' is required when '{}' is provided", param, if_param),
+            SqlQueryRule::RequiredIf { param, if_param, equals: Some(value) } => write!(f, "'{}' is required when '{}' is '{}'", param, if_param, value),
+            SqlQueryRule::MaxSpan { from, to, max_days } => write!(f, "'{}' and '{}' must be at most {} days apart", from, to, max_days),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Cycle de vie d'une requête (colonne `status` de la table des requêtes).
+///
+/// Seules les requêtes `Active` et `Deprecated` sont exécutables par défaut :
+/// `SqlQueryManager::get_sql_dynamic_query` refuse les brouillons et les requêtes désactivées
+/// (voir `SqlQueryManagerBuilder::allow_drafts` pour exécuter les brouillons, par exemple en recette).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlQueryStatus
+{
+    /// En préparation : enregistrée mais pas encore exposée
+    Draft,
+
+    /// En service (valeur par défaut lorsque la colonne est absente ou vide)
+    #[default]
+    Active,
+
+    /// Encore exécutable, mais chaque exécution produit un avertissement
+    Deprecated,
+
+    /// Retirée : plus aucune exécution
+    Disabled,
+}
+
+impl SqlQueryStatus {
+    /// Nom stable du statut, tel que stocké dans la colonne `status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SqlQueryStatus::Draft => "draft",
+            SqlQueryStatus::Active => "active",
+            SqlQueryStatus::Deprecated => "deprecated",
+            SqlQueryStatus::Disabled => "disabled",
+        }
+    }
+
+    /// Indique si une requête de ce statut peut être exécutée.
+    /// # Arguments
+    /// * `allow_drafts`: Si vrai, les brouillons sont exécutables
+    pub fn is_runnable(&self, allow_drafts: bool) -> bool {
+        match self {
+            SqlQueryStatus::Active | SqlQueryStatus::Deprecated => true,
+            SqlQueryStatus::Draft => allow_drafts,
+            SqlQueryStatus::Disabled => false,
+        }
+    }
+}
+
+impl fmt::Display for SqlQueryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for SqlQueryStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "draft" => Ok(SqlQueryStatus::Draft),
+            "active" => Ok(SqlQueryStatus::Active),
+            "deprecated" => Ok(SqlQueryStatus::Deprecated),
+            "disabled" => Ok(SqlQueryStatus::Disabled),
+            _ => Err(format!("unknown query status '{}' (expected draft, active, deprecated or disabled)", value)),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use similar::TextDiff;
+use crate::sql_query_definition::{SqlParamDefinition, SqlQueryDefinition};
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_repository::QueryRepository;
+
+/// Différence entre deux valeurs d'un champ (`None` : champ absent ou non renseigné).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlFieldChange
+{
+    pub field: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Différence portant sur un paramètre, identifié par son nom.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SqlParamChange
+{
+    /// Paramètre présent uniquement dans la cible
+    Added { param: SqlParamDefinition },
+
+    /// Paramètre présent uniquement dans la source
+    Removed { param: SqlParamDefinition },
+
+    /// Paramètre dont la définition diffère (type, ordre, caractère requis, valeur par défaut...)
+    Changed { param_name: String, fields: Vec<SqlFieldChange> },
+}
+
+/// Différence portant sur une requête.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SqlQueryChange
+{
+    /// Requête présente uniquement dans la cible
+    Added { definition: SqlQueryDefinition },
+
+    /// Requête présente uniquement dans la source
+    Removed { definition: SqlQueryDefinition },
+
+    /// Requête présente des deux côtés avec une définition différente
+    Changed {
+        /// Diff unifié du code SQL, `None` si le code est identique
+        sql_diff: Option<String>,
+        /// Autres champs de la requête modifiés (nom, rôles, politiques...)
+        fields: Vec<SqlFieldChange>,
+        /// Paramètres ajoutés, supprimés ou modifiés
+        params: Vec<SqlParamChange>,
+    },
+}
+
+/// Différence d'une requête entre la source et la cible.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SqlQueryDiff
+{
+    pub item_key: String,
+    #[serde(flatten)]
+    pub change: SqlQueryChange,
+}
+
+/// Différences entre deux registres de requêtes, de la source (`from`) vers la cible (`to`).
+/// Pour préparer une promotion de staging vers prod, comparer prod (source) à staging (cible) :
+/// les requêtes `Added` sont celles que la promotion créerait.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SqlRegistryDiff
+{
+    /// Requêtes ajoutées, supprimées ou modifiées, triées par item_key
+    pub queries: Vec<SqlQueryDiff>,
+    /// Item_keys des requêtes identiques des deux côtés, triées
+    pub unchanged: Vec<String>,
+}
+
+impl SqlRegistryDiff {
+    /// Compare les requêtes de deux dépôts (tables de deux environnements, fichiers, document exporté...).
+    /// Avec deux gestionnaires : `SqlRegistryDiff::between(prod.repository().as_ref(), staging.repository().as_ref())`.
+    /// # Arguments
+    /// * `from`: Registre source
+    /// * `to`: Registre cible
+    pub async fn between(from: &dyn QueryRepository, to: &dyn QueryRepository) -> Result<Self, SqlQueryEngineError> {
+        Ok(Self::compare(&from.definitions().await?, &to.definitions().await?))
+    }
+
+    /// Compare deux ensembles de définitions (par exemple les `queries` de deux documents exportés).
+    pub fn compare(from: &[SqlQueryDefinition], to: &[SqlQueryDefinition]) -> Self {
+        let from: BTreeMap<&str, &SqlQueryDefinition> = from.iter().map(|definition| (definition.item_key.as_str(), definition)).collect();
+        let to: BTreeMap<&str, &SqlQueryDefinition> = to.iter().map(|definition| (definition.item_key.as_str(), definition)).collect();
+        let item_keys: BTreeSet<&str> = from.keys().chain(to.keys()).copied().collect();
+
+        let mut diff = Self::default();
+        for item_key in item_keys {
+            let change = match (from.get(item_key), to.get(item_key)) {
+                (None, Some(after)) => Some(SqlQueryChange::Added { definition: (*after).clone() }),
+                (Some(before), None) => Some(SqlQueryChange::Removed { definition: (*before).clone() }),
+                (Some(before), Some(after)) => compare_definitions(before, after),
+                (None, None) => None,
+            };
+
+            match change {
+                Some(change) => diff.queries.push(SqlQueryDiff { item_key: item_key.to_string(), change }),
+                None => diff.unchanged.push(item_key.to_string()),
+            }
+        }
+
+        diff
+    }
+
+    /// Indique si les deux registres sont identiques.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Item_keys des requêtes présentes uniquement dans la cible.
+    pub fn added(&self) -> impl Iterator<Item = &str> {
+        self.item_keys(|change| matches!(change, SqlQueryChange::Added { .. }))
+    }
+
+    /// Item_keys des requêtes présentes uniquement dans la source.
+    pub fn removed(&self) -> impl Iterator<Item = &str> {
+        self.item_keys(|change| matches!(change, SqlQueryChange::Removed { .. }))
+    }
+
+    /// Item_keys des requêtes modifiées.
+    pub fn changed(&self) -> impl Iterator<Item = &str> {
+        self.item_keys(|change| matches!(change, SqlQueryChange::Changed { .. }))
+    }
+
+    fn item_keys(&self, predicate: impl Fn(&SqlQueryChange) -> bool) -> impl Iterator<Item = &str> {
+        self.queries.iter()
+            .filter(move |query| predicate(&query.change))
+            .map(|query| query.item_key.as_str())
+    }
+}
+
+/// Compare deux définitions d'une même requête, `None` si elles sont identiques.
+fn compare_definitions(before: &SqlQueryDefinition, after: &SqlQueryDefinition) -> Option<SqlQueryChange> {
+    let sql_diff = (before.sql_code != after.sql_code).then(|| {
+        TextDiff::from_lines(&with_final_newline(&before.sql_code), &with_final_newline(&after.sql_code))
+            .unified_diff()
+            .header(&format!("{} (from)", before.item_key), &format!("{} (to)", after.item_key))
+            .to_string()
+    });
+
+    let fields = compare_fields(query_fields(before), query_fields(after));
+    let params = compare_params(&before.params, &after.params);
+
+    match sql_diff.is_none() && fields.is_empty() && params.is_empty() {
+        true => None,
+        false => Some(SqlQueryChange::Changed { sql_diff, fields, params }),
+    }
+}
+
+fn with_final_newline(sql: &str) -> String {
+    format!("{}\n", sql.trim_end())
+}
+
+/// Champs d'une requête autres que l'item_key, le code SQL et les paramètres.
+fn query_fields(definition: &SqlQueryDefinition) -> Map<String, Value> {
+    let mut fields = to_map(definition);
+    for field in ["item_key", "sql_code", "params"] {
+        fields.remove(field);
+    }
+    fields
+}
+
+fn compare_params(before: &[SqlParamDefinition], after: &[SqlParamDefinition]) -> Vec<SqlParamChange> {
+    let positions = |params: &[SqlParamDefinition]| -> BTreeMap<String, (i32, SqlParamDefinition)> {
+        params.iter()
+            .zip(1..)
+            .map(|(param, position)| (param.param_name.clone(), (param.param_order.unwrap_or(position), param.clone())))
+            .collect()
+    };
+    let before = positions(before);
+    let after = positions(after);
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    names.into_iter()
+        .filter_map(|name| match (before.get(name), after.get(name)) {
+            (None, Some((_, param))) => Some(SqlParamChange::Added { param: param.clone() }),
+            (Some((_, param)), None) => Some(SqlParamChange::Removed { param: param.clone() }),
+            (Some(before), Some(after)) => {
+                let fields = compare_fields(param_fields(before), param_fields(after));
+                (!fields.is_empty()).then(|| SqlParamChange::Changed { param_name: name.clone(), fields })
+            },
+            (None, None) => None,
+        })
+        .collect()
+}
+
+/// Champs d'un paramètre, l'ordre effectif remplaçant `param_order`.
+fn param_fields((order, param): &(i32, SqlParamDefinition)) -> Map<String, Value> {
+    let mut fields = to_map(param);
+    fields.remove("param_name");
+    fields.insert("param_order".to_string(), Value::from(*order));
+    fields
+}
+
+fn to_map(value: &impl Serialize) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn compare_fields(before: Map<String, Value>, after: Map<String, Value>) -> Vec<SqlFieldChange> {
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields.into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| SqlFieldChange {
+            field: field.clone(),
+            before: before.get(field).cloned(),
+            after: after.get(field).cloned(),
+        })
+        .collect()
+}
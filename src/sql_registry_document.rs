@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use crate::sql_query_definition::SqlQueryDefinition;
+
+/// Sélection des requêtes exportées par `SqlQueryManager::export`.
+/// Un filtre vide sélectionne toutes les requêtes ; sinon une requête doit satisfaire chaque critère renseigné.
+/// Le filtre est conservé dans le document exporté : un import en mode `Sync` ne supprime que les requêtes qu'il sélectionne.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlExportFilter
+{
+    /// Préfixe de l'item_key (ex : `participant.`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// Liste explicite d'item_keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_keys: Option<Vec<String>>,
+}
+
+impl SqlExportFilter {
+    /// Filtre sélectionnant toutes les requêtes.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Ne sélectionne que les item_keys commençant par ce préfixe.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Ne sélectionne que les item_keys données.
+    pub fn with_item_keys<I, S>(mut self, item_keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.item_keys = Some(item_keys.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Indique si la requête de cette item_key est sélectionnée.
+    pub fn matches(&self, item_key: &str) -> bool {
+        self.prefix.as_deref().is_none_or(|prefix| item_key.starts_with(prefix))
+            && self.item_keys.as_ref().is_none_or(|item_keys| item_keys.iter().any(|key| key == item_key))
+    }
+}
+
+/// Document d'export / import du registre des requêtes (requêtes et paramètres), sérialisable en YAML ou JSON.
+/// # Exemple
+/// ```yaml
+/// filter:
+///   prefix: select.
+/// queries:
+///   - item_key: select.atelier
+///     name: Atelier
+///     sql_code: SELECT id, item_name FROM atelier WHERE id = $1
+///     params:
+///       - param_name: id
+///         param_type: BIGINT
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SqlRegistryDocument
+{
+    /// Filtre utilisé à l'export, délimitant les suppressions d'un import `Sync`
+    #[serde(default)]
+    pub filter: SqlExportFilter,
+
+    /// Définitions des requêtes, triées par item_key à l'export
+    #[serde(default)]
+    pub queries: Vec<SqlQueryDefinition>,
+}
+
+impl SqlRegistryDocument {
+    /// Crée un document à partir de définitions.
+    pub fn new(filter: SqlExportFilter, queries: Vec<SqlQueryDefinition>) -> Self {
+        Self { filter, queries }
+    }
+
+    /// Sérialise le document en YAML.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Lit un document YAML.
+    pub fn from_yaml(content: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// Sérialise le document en JSON indenté.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Lit un document JSON.
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sqlx::{Database, Pool};
+
+/// Réplicas en lecture du pool principal d'un `SqlQueryManager`, partagés entre ses clones.
+/// Les réplicas en bonne santé sont proposés à tour de rôle ; un réplica fermé est ignoré
+/// et un réplica dont la connexion a échoué est écarté pendant `retry_after`.
+/// # Champs
+/// - `pools`: Pools des réplicas
+/// - `retry_after`: Durée pendant laquelle un réplica injoignable est écarté
+/// - `next`: Position du prochain réplica proposé
+/// - `unavailable_until`: Date jusqu'à laquelle chaque réplica est écarté
+pub struct SqlReplicaSet<DB: Database>
+{
+    pools: Vec<Pool<DB>>,
+    retry_after: Duration,
+    next: Arc<AtomicUsize>,
+    unavailable_until: Arc<Mutex<Vec<Option<Instant>>>>,
+}
+
+impl<DB: Database> SqlReplicaSet<DB> {
+    /// Durée d'éviction d'un réplica injoignable utilisée lorsqu'aucune durée n'est précisée.
+    pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+    /// Crée un ensemble de réplicas.
+    /// # Arguments
+    /// * `pools`: Pools des réplicas
+    /// * `retry_after`: Durée pendant laquelle un réplica injoignable est écarté
+    pub fn new(pools: Vec<Pool<DB>>, retry_after: Duration) -> Self {
+        let unavailable_until = vec![None; pools.len()];
+        Self {
+            pools,
+            retry_after,
+            next: Arc::new(AtomicUsize::new(0)),
+            unavailable_until: Arc::new(Mutex::new(unavailable_until)),
+        }
+    }
+
+    /// Nombre de réplicas.
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Indique si aucun réplica n'est configuré.
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Retourne le pool d'un réplica.
+    /// # Arguments
+    /// * `index`: Position du réplica (dans l'ordre de déclaration)
+    pub fn pool(&self, index: usize) -> Option<&Pool<DB>> {
+        self.pools.get(index)
+    }
+
+    /// Indique si un réplica peut recevoir des requêtes : pool ouvert et non écarté.
+    /// # Arguments
+    /// * `index`: Position du réplica (dans l'ordre de déclaration)
+    pub fn is_healthy(&self, index: usize) -> bool {
+        let unavailable_until = self.unavailable_until.lock().unwrap_or_else(|e| e.into_inner());
+        self.is_available(index, &unavailable_until, Instant::now())
+    }
+
+    /// Nombre de réplicas pouvant recevoir des requêtes.
+    pub fn healthy_count(&self) -> usize {
+        (0..self.len()).filter(|index| self.is_healthy(*index)).count()
+    }
+
+    /// Réplicas en bonne santé dans l'ordre où les essayer, en commençant par le suivant du tour de rôle.
+    pub(crate) fn candidates(&self) -> Vec<(usize, Pool<DB>)>
+    {
+        if self.pools.is_empty() {
+            return Vec::new();
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.pools.len();
+        let unavailable_until = self.unavailable_until.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        (0..self.pools.len())
+            .map(|offset| (start + offset) % self.pools.len())
+            .filter(|index| self.is_available(*index, &unavailable_until, now))
+            .map(|index| (index, self.pools[index].clone()))
+            .collect()
+    }
+
+    /// Écarte un réplica injoignable pendant `retry_after`.
+    pub(crate) fn mark_unavailable(&self, index: usize)
+    {
+        let mut unavailable_until = self.unavailable_until.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = unavailable_until.get_mut(index) {
+            *until = Some(Instant::now() + self.retry_after);
+        }
+    }
+
+    fn is_available(&self, index: usize, unavailable_until: &[Option<Instant>], now: Instant) -> bool {
+        let expired = match unavailable_until.get(index) {
+            Some(Some(until)) => *until <= now,
+            _ => true,
+        };
+        expired && self.pools.get(index).is_some_and(|pool| !pool.is_closed())
+    }
+}
+
+impl<DB: Database> Clone for SqlReplicaSet<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pools: self.pools.clone(),
+            retry_after: self.retry_after,
+            next: self.next.clone(),
+            unavailable_until: self.unavailable_until.clone(),
+        }
+    }
+}
+
+impl<DB: Database> std::fmt::Debug for SqlReplicaSet<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlReplicaSet")
+            .field("pools", &self.pools)
+            .field("retry_after", &self.retry_after)
+            .field("healthy", &self.healthy_count())
+            .finish()
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{Query, SetExpr, Statement};
+use sqlparser::dialect::{Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+
+/// Nature d'une requête SQL, de la moins à la plus risquée.
+///
+/// L'ordre permet d'exprimer une politique comme un niveau maximal :
+/// `ReadOnly` n'autorise que les lectures, `Dml` ajoute les écritures, `Ddl` autorise tout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlStatementKind
+{
+    /// Lecture seule : `SELECT`, `VALUES`, `TABLE`, `EXPLAIN` (sans `ANALYZE`), `SHOW`
+    ReadOnly,
+
+    /// Modification des données : `INSERT`, `UPDATE`, `DELETE`, `MERGE`, `COPY`, `CALL`,
+    /// `SELECT ... FOR UPDATE`
+    Dml,
+
+    /// Toute autre instruction : schéma (`CREATE`, `ALTER`, `DROP`, `TRUNCATE`),
+    /// droits (`GRANT`), session ou transaction (`SET`, `COMMIT`), `SELECT ... INTO`...
+    Ddl,
+}
+
+impl SqlStatementKind {
+    /// Nom stable de la nature, tel que stocké dans la colonne `statement_kind`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SqlStatementKind::ReadOnly => "read_only",
+            SqlStatementKind::Dml => "dml",
+            SqlStatementKind::Ddl => "ddl",
+        }
+    }
+}
+
+impl fmt::Display for SqlStatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for SqlStatementKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "read_only" => Ok(SqlStatementKind::ReadOnly),
+            "dml" => Ok(SqlStatementKind::Dml),
+            "ddl" => Ok(SqlStatementKind::Ddl),
+            _ => Err(format!("unknown statement kind '{}' (expected read_only, dml or ddl)", value)),
+        }
+    }
+}
+
+/// Dialecte SQL utilisé pour analyser le code des requêtes, celui de la base qui l'exécute
+/// (voir `SqlBackend::sql_dialect`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SqlDialect
+{
+    #[default]
+    PostgreSql,
+    Sqlite,
+    MySql,
+}
+
+impl SqlDialect {
+    fn parser_dialect(&self) -> &'static dyn Dialect {
+        match self {
+            SqlDialect::PostgreSql => &PostgreSqlDialect {},
+            SqlDialect::Sqlite => &SQLiteDialect {},
+            SqlDialect::MySql => &MySqlDialect {},
+        }
+    }
+}
+
+/// Raison pour laquelle un code SQL est refusé par l'analyse de sécurité.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "violation", rename_all = "snake_case")]
+pub enum SqlSafetyViolation
+{
+    /// Le code SQL ne peut pas être analysé
+    ParseError { message: String },
+
+    /// Le code SQL ne contient aucune instruction
+    EmptyStatement,
+
+    /// Le code SQL contient plusieurs instructions
+    MultipleStatements { count: usize },
+
+    /// La nature de l'instruction dépasse le niveau autorisé
+    ForbiddenStatement { kind: SqlStatementKind, max_kind: SqlStatementKind },
+
+    /// La nature déclarée pour la requête (colonne `statement_kind`) est inconnue
+    InvalidStatementKind { value: String },
+}
+
+impl fmt::Display for SqlSafetyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlSafetyViolation::ParseError { message } => write!(f, "SQL code cannot be parsed: {}", message),
+            SqlSafetyViolation::EmptyStatement => write!(f, "SQL code contains no statement"),
+            SqlSafetyViolation::MultipleStatements { count } => write!(f, "SQL code contains {} statements, only one is allowed", count),
+            SqlSafetyViolation::ForbiddenStatement { kind, max_kind } =>
+                write!(f, "{} statement is not allowed (maximum allowed: {})", kind, max_kind),
+            SqlSafetyViolation::InvalidStatementKind { value } =>
+                write!(f, "unknown statement kind '{}' (expected read_only, dml or ddl)", value),
+        }
+    }
+}
+
+/// Analyse un code SQL dans le dialecte donné et renvoie sa nature.
+///
+/// Le code doit contenir exactement une instruction. L'analyse est statique :
+/// les effets de bord des fonctions appelées (ex : `SELECT nextval(...)`) ne sont pas détectés.
+///
+/// # Arguments
+/// * `sql_code` - Le code SQL à analyser
+/// * `dialect` - Le dialecte de la base qui exécute le code
+///
+/// # Returns
+/// * `Err(SqlSafetyViolation)` - Si le code ne peut pas être analysé, est vide ou contient plusieurs instructions
+pub fn analyze_sql(sql_code: &str, dialect: SqlDialect) -> Result<SqlStatementKind, SqlSafetyViolation>
+{
+    let statements = Parser::parse_sql(dialect.parser_dialect(), sql_code)
+        .map_err(|e| SqlSafetyViolation::ParseError { message: e.to_string() })?;
+
+    match statements.as_slice() {
+        [] => Err(SqlSafetyViolation::EmptyStatement),
+        [statement] => Ok(statement_kind(statement)),
+        _ => Err(SqlSafetyViolation::MultipleStatements { count: statements.len() }),
+    }
+}
+
+/// Analyse un code SQL et vérifie que sa nature ne dépasse pas `max_kind`.
+///
+/// # Arguments
+/// * `sql_code` - Le code SQL à analyser
+/// * `max_kind` - La nature la plus risquée autorisée
+/// * `dialect` - Le dialecte de la base qui exécute le code
+pub fn check_sql_safety(sql_code: &str, max_kind: SqlStatementKind, dialect: SqlDialect) -> Result<SqlStatementKind, SqlSafetyViolation>
+{
+    let kind = analyze_sql(sql_code, dialect)?;
+    if kind > max_kind {
+        return Err(SqlSafetyViolation::ForbiddenStatement { kind, max_kind });
+    }
+    Ok(kind)
+}
+
+fn statement_kind(statement: &Statement) -> SqlStatementKind
+{
+    match statement {
+        Statement::Query(query) => query_kind(query),
+        Statement::Explain { analyze: true, statement, .. } => statement_kind(statement),
+        Statement::Explain { .. }
+        | Statement::ExplainTable { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowViews { .. }
+        | Statement::ShowSchemas { .. }
+        | Statement::ShowDatabases { .. } => SqlStatementKind::ReadOnly,
+        Statement::Insert(_)
+        | Statement::Update { .. }
+        | Statement::Delete(_)
+        | Statement::Merge { .. }
+        | Statement::Copy { .. }
+        | Statement::Call(_) => SqlStatementKind::Dml,
+        _ => SqlStatementKind::Ddl,
+    }
+}
+
+fn query_kind(query: &Query) -> SqlStatementKind
+{
+    let mut kind = set_expr_kind(&query.body);
+    if !query.locks.is_empty() {
+        kind = kind.max(SqlStatementKind::Dml);
+    }
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            kind = kind.max(query_kind(&cte.query));
+        }
+    }
+    kind
+}
+
+fn set_expr_kind(set_expr: &SetExpr) -> SqlStatementKind
+{
+    match set_expr {
+        // SELECT ... INTO crée une table
+        SetExpr::Select(select) if select.into.is_some() => SqlStatementKind::Ddl,
+        SetExpr::Select(_) | SetExpr::Values(_) | SetExpr::Table(_) => SqlStatementKind::ReadOnly,
+        SetExpr::Query(query) => query_kind(query),
+        SetExpr::SetOperation { left, right, .. } => set_expr_kind(left).max(set_expr_kind(right)),
+        SetExpr::Insert(statement) | SetExpr::Update(statement) => statement_kind(statement).max(SqlStatementKind::Dml),
+    }
+}
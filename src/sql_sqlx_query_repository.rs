@@ -0,0 +1,484 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{ColumnIndex, Database, Decode, Encode, Executor, IntoArguments, MySql, Pool, Row, Sqlite, Type};
+use crate::sql_backend::SqlBackend;
+use crate::sql_column_policy::SqlColumnPolicy;
+use crate::sql_param_constraints::SqlParamConstraints;
+use crate::sql_query::SqlQuery;
+use crate::sql_query_definition::SqlQueryDefinition;
+use crate::sql_query_engine_error::SqlQueryEngineError;
+use crate::sql_query_param::SqlQueryParam;
+use crate::sql_query_repository::QueryRepository;
+use crate::sql_query_rule::SqlQueryRule;
+
+/// Définitions de requêtes stockées dans deux tables SQLite (requêtes et paramètres).
+pub type SqliteQueryRepository = SqlxQueryRepository<Sqlite>;
+
+/// Définitions de requêtes stockées dans deux tables MySQL / MariaDB (requêtes et paramètres).
+pub type MySqlQueryRepository = SqlxQueryRepository<MySql>;
+
+/// Backend dont `SqlxQueryRepository` sait créer les tables des requêtes et des paramètres.
+pub trait SqlxRegistryBackend: SqlBackend
+{
+    /// Instructions `CREATE TABLE IF NOT EXISTS` des tables des requêtes et des paramètres.
+    fn create_tables_sql(table_query: &str, table_query_params: &str) -> [String; 2];
+
+    /// Nombre de lignes modifiées par une instruction.
+    fn rows_affected(result: &Self::QueryResult) -> u64;
+}
+
+/// SQLite n'ayant ni tableaux ni JSONB, `allowed_roles`, `rules`, `column_policies` et `constraints`
+/// sont stockées en texte JSON.
+impl SqlxRegistryBackend for Sqlite
+{
+    fn rows_affected(result: &Self::QueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn create_tables_sql(table_query: &str, table_query_params: &str) -> [String; 2] {
+        [
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    sql_code TEXT NOT NULL,
+                    item_key TEXT NOT NULL UNIQUE,
+                    sign TEXT,
+                    rules TEXT,
+                    allowed_roles TEXT,
+                    statement_kind TEXT,
+                    read_only BOOLEAN,
+                    column_policies TEXT,
+                    status TEXT,
+                    sunset_at TEXT,
+                    datasource TEXT,
+                    needs_fresh_data BOOLEAN
+                )",
+                table_query
+            ),
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    item_key TEXT NOT NULL REFERENCES {}(item_key) ON DELETE CASCADE,
+                    param_name TEXT NOT NULL,
+                    param_type TEXT NOT NULL,
+                    param_order INTEGER NOT NULL,
+                    is_required INTEGER NOT NULL DEFAULT 1,
+                    default_value TEXT,
+                    description TEXT,
+                    constraints TEXT,
+                    sensitive BOOLEAN
+                )",
+                table_query_params,
+                table_query
+            ),
+        ]
+    }
+}
+
+/// MySQL n'ayant pas de tableaux, `allowed_roles` est stockée en JSON comme `rules`, `column_policies`
+/// et `constraints` (colonnes `JSON`, ou texte JSON avec MariaDB).
+impl SqlxRegistryBackend for MySql
+{
+    fn rows_affected(result: &Self::QueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn create_tables_sql(table_query: &str, table_query_params: &str) -> [String; 2] {
+        [
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id INT AUTO_INCREMENT PRIMARY KEY,
+                    name VARCHAR(255) NOT NULL,
+                    description TEXT,
+                    sql_code TEXT NOT NULL,
+                    item_key VARCHAR(255) NOT NULL UNIQUE,
+                    sign VARCHAR(255),
+                    rules JSON,
+                    allowed_roles JSON,
+                    statement_kind VARCHAR(16),
+                    read_only BOOLEAN,
+                    column_policies JSON,
+                    status VARCHAR(16),
+                    sunset_at DATETIME(6),
+                    datasource VARCHAR(255),
+                    needs_fresh_data BOOLEAN
+                )",
+                table_query
+            ),
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id INT AUTO_INCREMENT PRIMARY KEY,
+                    item_key VARCHAR(255) NOT NULL,
+                    param_name VARCHAR(255) NOT NULL,
+                    param_type VARCHAR(32) NOT NULL,
+                    param_order INT NOT NULL,
+                    is_required INT NOT NULL DEFAULT 1,
+                    default_value TEXT,
+                    description TEXT,
+                    constraints JSON,
+                    sensitive BOOLEAN,
+                    FOREIGN KEY (item_key) REFERENCES {}(item_key) ON DELETE CASCADE
+                )",
+                table_query_params,
+                table_query
+            ),
+        ]
+    }
+}
+
+/// Type d'une colonne des tables du registre, lu et écrit tel quel par le backend.
+pub trait SqlxRegistryColumn<DB: Database>: for<'q> Encode<'q, DB> + for<'r> Decode<'r, DB> + Type<DB> + Send + Sync {}
+
+impl<DB: Database, T> SqlxRegistryColumn<DB> for T
+where
+    T: for<'q> Encode<'q, DB> + for<'r> Decode<'r, DB> + Type<DB> + Send + Sync,
+{}
+
+/// Définitions de requêtes stockées dans deux tables (requêtes et paramètres) d'une base
+/// autre que PostgreSQL (voir `SqliteQueryRepository` et `MySqlQueryRepository`).
+///
+/// Les colonnes sont celles des tables PostgreSQL, les colonnes JSON étant stockées selon le backend
+/// (voir `SqlxRegistryBackend::create_tables_sql`). Les colonnes optionnelles peuvent être absentes des tables.
+/// Le code SQL du dépôt utilise les placeholders `$1, $2, ...`, traduits par `SqlBackend::translate_placeholders`.
+pub struct SqlxQueryRepository<DB: Database>
+{
+    pool: Pool<DB>,
+    table_query: String,
+    table_query_params: String,
+}
+
+impl<DB: Database> Clone for SqlxQueryRepository<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table_query: self.table_query.clone(),
+            table_query_params: self.table_query_params.clone(),
+        }
+    }
+}
+
+impl<DB: Database> fmt::Debug for SqlxQueryRepository<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlxQueryRepository")
+            .field("backend", &DB::NAME)
+            .field("pool", &self.pool)
+            .field("table_query", &self.table_query)
+            .field("table_query_params", &self.table_query_params)
+            .finish()
+    }
+}
+
+impl<DB: Database> SqlxQueryRepository<DB> {
+    /// Crée une nouvelle instance de `SqlxQueryRepository`.
+    /// # Arguments
+    /// * `pool`: Pool de connexions à la base
+    /// * `table_query`: Nom de la table contenant les requêtes
+    /// * `table_query_params`: Nom de la table contenant les paramètres de requête
+    pub fn new(pool: Pool<DB>, table_query: impl Into<String>, table_query_params: impl Into<String>) -> Self {
+        Self {
+            pool,
+            table_query: table_query.into(),
+            table_query_params: table_query_params.into(),
+        }
+    }
+
+    /// Retourne le nom de la table contenant les requêtes.
+    pub fn table_query(&self) -> &str {
+        &self.table_query
+    }
+
+    /// Retourne le nom de la table contenant les paramètres de requête.
+    pub fn table_query_params(&self) -> &str {
+        &self.table_query_params
+    }
+
+    fn register_error(&self, item_key: &str, source: sqlx::Error) -> SqlQueryEngineError
+    {
+        SqlQueryEngineError::ErrorRegisterSqlQuery {
+            table: self.table_query.clone(),
+            item_key: item_key.to_string(),
+            source,
+        }
+    }
+
+    fn get_query_error(&self, item_key: &str) -> impl FnOnce(sqlx::Error) -> SqlQueryEngineError + '_
+    {
+        let item_key = item_key.to_string();
+        move |source| SqlQueryEngineError::ErrorGetSqlQuery {
+            table: self.table_query.clone(),
+            item_key,
+            source,
+        }
+    }
+}
+
+impl<DB> SqlxQueryRepository<DB>
+where
+    DB: SqlxRegistryBackend,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'a> &'a str: ColumnIndex<DB::Row> + Encode<'a, DB> + Type<DB>,
+    i32: SqlxRegistryColumn<DB>,
+    String: SqlxRegistryColumn<DB>,
+    Option<String>: SqlxRegistryColumn<DB>,
+    Option<bool>: SqlxRegistryColumn<DB>,
+    Option<DateTime<Utc>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<String>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlQueryRule>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlColumnPolicy>>>: SqlxRegistryColumn<DB>,
+    Option<Json<SqlParamConstraints>>: SqlxRegistryColumn<DB>,
+{
+    /// Crée les tables des requêtes et des paramètres si elles n'existent pas.
+    pub async fn create_tables(&self) -> Result<(), SqlQueryEngineError>
+    {
+        for statement in DB::create_tables_sql(&self.table_query, &self.table_query_params) {
+            sqlx::query::<DB>(&statement)
+                .execute(&self.pool)
+                .await
+                .map_err(|source| self.register_error("", source))?;
+        }
+        Ok(())
+    }
+
+    /// Enregistre (ou remplace) une requête et ses paramètres à partir de sa définition, dans une transaction.
+    /// La définition n'est pas vérifiée (voir `SqlQueryDefinition::validate`).
+    pub async fn insert_definition(&self, definition: &SqlQueryDefinition) -> Result<(), SqlQueryEngineError>
+    {
+        let item_key = definition.item_key.as_str();
+        let error = |source| self.register_error(item_key, source);
+        let (query, params) = definition.to_parts();
+
+        let mut transaction = self.pool.begin().await.map_err(error)?;
+        self.delete(&mut transaction, item_key).await.map_err(error)?;
+
+        let insert_query = Self::sql(format!(
+            "INSERT INTO {} (name, description, sql_code, item_key, sign, rules, allowed_roles, statement_kind, read_only, column_policies, status, sunset_at, datasource, needs_fresh_data)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+            self.table_query
+        ));
+        sqlx::query::<DB>(&insert_query)
+            .bind(&query.name)
+            .bind(&query.description)
+            .bind(&query.sql_code)
+            .bind(&query.item_key)
+            .bind(&query.sign)
+            .bind(&query.rules)
+            .bind(query.allowed_roles.clone().map(Json))
+            .bind(&query.statement_kind)
+            .bind(query.read_only)
+            .bind(&query.column_policies)
+            .bind(&query.status)
+            .bind(query.sunset_at)
+            .bind(&query.datasource)
+            .bind(query.needs_fresh_data)
+            .execute(&mut *transaction)
+            .await
+            .map_err(error)?;
+
+        let insert_param = Self::sql(format!(
+            "INSERT INTO {} (item_key, param_name, param_type, param_order, is_required, default_value, description, constraints, sensitive)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            self.table_query_params
+        ));
+        for param in &params {
+            sqlx::query::<DB>(&insert_param)
+                .bind(item_key)
+                .bind(&param.param_name)
+                .bind(&param.param_type)
+                .bind(param.param_order)
+                .bind(param.is_required)
+                .bind(&param.default_value)
+                .bind(&param.description)
+                .bind(&param.constraints)
+                .bind(param.sensitive)
+                .execute(&mut *transaction)
+                .await
+                .map_err(error)?;
+        }
+
+        transaction.commit().await.map_err(error)
+    }
+
+    /// Supprime une requête et ses paramètres, renvoyant `true` si elle existait.
+    pub async fn remove(&self, item_key: &str) -> Result<bool, SqlQueryEngineError>
+    {
+        let error = |source| self.register_error(item_key, source);
+
+        let mut transaction = self.pool.begin().await.map_err(error)?;
+        let removed = self.delete(&mut transaction, item_key).await.map_err(error)?;
+        transaction.commit().await.map_err(error)?;
+        Ok(removed)
+    }
+
+    async fn delete(&self, connection: &mut DB::Connection, item_key: &str) -> Result<bool, sqlx::Error>
+    {
+        let delete_params = Self::sql(format!("DELETE FROM {} WHERE item_key = $1", self.table_query_params));
+        sqlx::query::<DB>(&delete_params)
+            .bind(item_key)
+            .execute(&mut *connection)
+            .await?;
+        let delete_query = Self::sql(format!("DELETE FROM {} WHERE item_key = $1", self.table_query));
+        let result = sqlx::query::<DB>(&delete_query)
+            .bind(item_key)
+            .execute(&mut *connection)
+            .await?;
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    /// Traduit les placeholders `$n` du code SQL du dépôt dans la syntaxe du backend.
+    fn sql(sql: String) -> String
+    {
+        DB::translate_placeholders(&sql).into_owned()
+    }
+}
+
+#[async_trait]
+impl<DB> QueryRepository for SqlxQueryRepository<DB>
+where
+    DB: SqlxRegistryBackend,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'a> &'a str: ColumnIndex<DB::Row> + Encode<'a, DB> + Type<DB>,
+    i32: SqlxRegistryColumn<DB>,
+    String: SqlxRegistryColumn<DB>,
+    Option<String>: SqlxRegistryColumn<DB>,
+    Option<bool>: SqlxRegistryColumn<DB>,
+    Option<DateTime<Utc>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<String>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlQueryRule>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlColumnPolicy>>>: SqlxRegistryColumn<DB>,
+    Option<Json<SqlParamConstraints>>: SqlxRegistryColumn<DB>,
+{
+    async fn get_query(&self, item_key: &str) -> Result<Option<SqlQuery>, SqlQueryEngineError>
+    {
+        let query = Self::sql(format!("SELECT * FROM {} WHERE item_key = $1", self.table_query));
+
+        let row = sqlx::query::<DB>(&query)
+            .bind(item_key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(self.get_query_error(item_key))?;
+
+        row.as_ref()
+            .map(sql_query_from_row::<DB>)
+            .transpose()
+            .map_err(self.get_query_error(item_key))
+    }
+
+    async fn get_params(&self, item_key: &str) -> Result<Option<Vec<SqlQueryParam>>, SqlQueryEngineError>
+    {
+        if self.get_query(item_key).await?.is_none() {
+            return Ok(None);
+        }
+
+        let query = Self::sql(format!("SELECT * FROM {} WHERE item_key = $1 ORDER BY param_order", self.table_query_params));
+        let error = |source| SqlQueryEngineError::ErrorGetSqlQueryParam {
+            table: self.table_query_params.clone(),
+            item_key: item_key.to_string(),
+            source,
+        };
+
+        let rows = sqlx::query::<DB>(&query)
+            .bind(item_key)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(error)?;
+
+        rows.iter()
+            .map(sql_query_param_from_row::<DB>)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+            .map_err(error)
+    }
+
+    async fn list(&self) -> Result<Vec<SqlQuery>, SqlQueryEngineError>
+    {
+        let query = format!("SELECT * FROM {} ORDER BY item_key", self.table_query);
+
+        let rows = sqlx::query::<DB>(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(self.get_query_error(""))?;
+
+        rows.iter()
+            .map(sql_query_from_row::<DB>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(self.get_query_error(""))
+    }
+}
+
+/// Lit une requête, les rôles autorisés étant stockés en JSON.
+fn sql_query_from_row<DB>(row: &DB::Row) -> Result<SqlQuery, sqlx::Error>
+where
+    DB: Database,
+    for<'a> &'a str: ColumnIndex<DB::Row>,
+    i32: SqlxRegistryColumn<DB>,
+    String: SqlxRegistryColumn<DB>,
+    Option<String>: SqlxRegistryColumn<DB>,
+    Option<bool>: SqlxRegistryColumn<DB>,
+    Option<DateTime<Utc>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<String>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlQueryRule>>>: SqlxRegistryColumn<DB>,
+    Option<Json<Vec<SqlColumnPolicy>>>: SqlxRegistryColumn<DB>,
+{
+    let mut query = SqlQuery::new(
+        row.try_get("id")?,
+        row.try_get("name")?,
+        row.try_get("description")?,
+        row.try_get("sql_code")?,
+        row.try_get("item_key")?,
+        row.try_get("sign")?,
+    );
+    query.rules = optional::<DB, _>(row, "rules")?;
+    query.allowed_roles = optional::<DB, Json<Vec<String>>>(row, "allowed_roles")?.map(|roles| roles.0);
+    query.statement_kind = optional::<DB, _>(row, "statement_kind")?;
+    query.read_only = optional::<DB, _>(row, "read_only")?;
+    query.column_policies = optional::<DB, _>(row, "column_policies")?;
+    query.status = optional::<DB, _>(row, "status")?;
+    query.sunset_at = optional::<DB, _>(row, "sunset_at")?;
+    query.datasource = optional::<DB, _>(row, "datasource")?;
+    query.needs_fresh_data = optional::<DB, _>(row, "needs_fresh_data")?;
+    Ok(query)
+}
+
+fn sql_query_param_from_row<DB>(row: &DB::Row) -> Result<SqlQueryParam, sqlx::Error>
+where
+    DB: Database,
+    for<'a> &'a str: ColumnIndex<DB::Row>,
+    i32: SqlxRegistryColumn<DB>,
+    String: SqlxRegistryColumn<DB>,
+    Option<String>: SqlxRegistryColumn<DB>,
+    Option<bool>: SqlxRegistryColumn<DB>,
+    Option<Json<SqlParamConstraints>>: SqlxRegistryColumn<DB>,
+{
+    Ok(SqlQueryParam {
+        id: row.try_get("id")?,
+        param_name: row.try_get("param_name")?,
+        param_type: row.try_get("param_type")?,
+        param_order: row.try_get("param_order")?,
+        is_required: row.try_get("is_required")?,
+        default_value: row.try_get("default_value")?,
+        description: row.try_get("description")?,
+        item_key: row.try_get("item_key")?,
+        constraints: optional::<DB, _>(row, "constraints")?,
+        sensitive: optional::<DB, _>(row, "sensitive")?,
+    })
+}
+
+/// Valeur d'une colonne optionnelle : `None` si elle est nulle ou absente de la table.
+fn optional<DB, T>(row: &DB::Row, column: &str) -> Result<Option<T>, sqlx::Error>
+where
+    DB: Database,
+    for<'a> &'a str: ColumnIndex<DB::Row>,
+    Option<T>: for<'r> Decode<'r, DB> + Type<DB>,
+{
+    match row.try_get::<Option<T>, _>(column) {
+        Err(sqlx::Error::ColumnNotFound(_)) => Ok(None),
+        value => value,
+    }
+}